@@ -0,0 +1,49 @@
+// A little-endian, 64-bit relocatable file with the given sections (name, type
+// and contents), after the null section and followed by .shstrtab.
+pub fn mk_elf64(sections: &[(String, u32, Vec<u8>)]) -> Vec<u8> {
+    fn align(elf: &mut Vec<u8>) {
+        let len = (elf.len() + 7) & !7;
+        elf.resize(len, 0);
+    }
+    fn put(elf: &mut Vec<u8>, offset: usize, bytes: &[u8]) {
+        elf[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    let mut elf = vec![0; 64];
+    let mut shstrtab = vec![0];
+    // Name, type, offset and size of each section header.
+    let mut headers = vec![(0, 0, 0, 0)];
+    for &(ref name, type_, ref data) in sections {
+        align(&mut elf);
+        headers.push((shstrtab.len(), type_, elf.len(), data.len()));
+        shstrtab.extend_from_slice(name.as_bytes());
+        shstrtab.push(0);
+        elf.extend_from_slice(data);
+    }
+    let name = shstrtab.len();
+    shstrtab.extend_from_slice(b".shstrtab\0");
+    headers.push((name, 3, elf.len(), shstrtab.len()));
+    elf.extend_from_slice(&shstrtab);
+    align(&mut elf);
+
+    let sh_offset = elf.len();
+    put(&mut elf, 0, b"\x7fELF\x02\x01\x01");
+    put(&mut elf, 16, &1u16.to_le_bytes());
+    put(&mut elf, 18, &0x3eu16.to_le_bytes());
+    put(&mut elf, 20, &1u32.to_le_bytes());
+    put(&mut elf, 40, &(sh_offset as u64).to_le_bytes());
+    put(&mut elf, 52, &64u16.to_le_bytes());
+    put(&mut elf, 58, &64u16.to_le_bytes());
+    put(&mut elf, 60, &(headers.len() as u16).to_le_bytes());
+    put(&mut elf, 62, &(headers.len() as u16 - 1).to_le_bytes());
+
+    for &(name, type_, offset, size) in &headers {
+        let header = elf.len();
+        elf.resize(header + 64, 0);
+        put(&mut elf, header, &(name as u32).to_le_bytes());
+        put(&mut elf, header + 4, &type_.to_le_bytes());
+        put(&mut elf, header + 24, &(offset as u64).to_le_bytes());
+        put(&mut elf, header + 32, &(size as u64).to_le_bytes());
+    }
+    elf
+}
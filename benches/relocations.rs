@@ -0,0 +1,74 @@
+#[macro_use]
+extern crate criterion;
+extern crate xmas_elf;
+
+use criterion::Criterion;
+use xmas_elf::ElfFile;
+use xmas_elf::header::Class;
+use xmas_elf::sections::SectionData;
+
+mod common;
+
+const RELOCATION_COUNT: u64 = 100_000;
+
+// A single .rela.text section with `RELOCATION_COUNT` entries.
+fn mk_elf() -> Vec<u8> {
+    let mut rela = vec![];
+    for i in 0..RELOCATION_COUNT {
+        // R_X86_64_PLT32 against symbol `i % 100`.
+        for &word in &[i * 4, (i % 100) << 32 | 4, (-4i64) as u64] {
+            rela.extend_from_slice(&word.to_le_bytes());
+        }
+    }
+    common::mk_elf64(&[(".rela.text".to_owned(), 4, rela)])
+}
+
+// Reading every relocation, either matching on the width for each one (as a
+// width-independent accessor would), or once for the whole table after
+// checking `ElfFile::class`.
+fn relocations(c: &mut Criterion) {
+    let input = mk_elf();
+    let elf = ElfFile::new(&input).unwrap();
+    let data = elf.find_section_by_name(".rela.text").unwrap().get_data(&elf).unwrap();
+
+    c.bench_function("get_data", |b| {
+        let sect = elf.find_section_by_name(".rela.text").unwrap();
+        b.iter(|| sect.get_data(&elf).unwrap())
+    });
+
+    c.bench_function("dispatch per relocation", |b| {
+        b.iter(|| {
+            let mut sum = 0u64;
+            for i in 0..RELOCATION_COUNT as usize {
+                sum = sum.wrapping_add(match data {
+                    SectionData::Rela32(entries) => {
+                        entries[i].get_offset() as u64 + entries[i].get_addend() as u64
+                    }
+                    SectionData::Rela64(entries) => {
+                        entries[i].get_offset().wrapping_add(entries[i].get_addend())
+                    }
+                    _ => unreachable!(),
+                });
+            }
+            criterion::black_box(sum)
+        })
+    });
+
+    c.bench_function("dispatch once", |b| {
+        b.iter(|| {
+            let mut sum = 0u64;
+            match (elf.class(), &data) {
+                (Class::SixtyFour, &SectionData::Rela64(entries)) => {
+                    for entry in entries {
+                        sum = sum.wrapping_add(entry.get_offset().wrapping_add(entry.get_addend()));
+                    }
+                }
+                _ => unreachable!(),
+            }
+            criterion::black_box(sum)
+        })
+    });
+}
+
+criterion_group!(benches, relocations);
+criterion_main!(benches);
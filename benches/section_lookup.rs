@@ -0,0 +1,49 @@
+#[macro_use]
+extern crate criterion;
+extern crate xmas_elf;
+
+use criterion::Criterion;
+use xmas_elf::ElfFile;
+use xmas_elf::sections::SectionNameIndex;
+
+mod common;
+
+const SECTION_COUNT: usize = 5000;
+
+// Empty sections named like those from `-ffunction-sections`.
+fn mk_elf() -> Vec<u8> {
+    let sections: Vec<_> = (0..SECTION_COUNT)
+        .map(|i| (format!(".text.function_{}", i), 1, vec![]))
+        .collect();
+    common::mk_elf64(&sections)
+}
+
+fn section_lookup(c: &mut Criterion) {
+    let input = mk_elf();
+    let elf = ElfFile::new(&input).unwrap();
+    let names: Vec<_> = (0..SECTION_COUNT).step_by(SECTION_COUNT / 50)
+                                          .map(|i| format!(".text.function_{}", i))
+                                          .collect();
+
+    c.bench_function("find_section_by_name", |b| {
+        b.iter(|| {
+            for name in &names {
+                assert!(elf.find_section_by_name(name).is_some());
+            }
+        })
+    });
+
+    c.bench_function("SectionNameIndex::new", |b| b.iter(|| SectionNameIndex::new(&elf)));
+
+    let index = SectionNameIndex::new(&elf);
+    c.bench_function("SectionNameIndex::find", |b| {
+        b.iter(|| {
+            for name in &names {
+                assert!(index.find(&elf, name).is_some());
+            }
+        })
+    });
+}
+
+criterion_group!(benches, section_lookup);
+criterion_main!(benches);
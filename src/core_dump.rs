@@ -0,0 +1,61 @@
+//! Notes found in core dumps (`Core` files), which describe the state of the
+//! dumped process. These are owned by "CORE" and laid out as on Linux.
+
+use zero::read;
+
+// Types of notes owned by "CORE".
+pub const NT_PRSTATUS: u32 = 1;
+pub const NT_FPREGSET: u32 = 2;
+pub const NT_PRPSINFO: u32 = 3;
+pub const NT_AUXV: u32 = 6;
+
+/// An `NT_PRSTATUS` note (`struct elf_prstatus`): the status of one thread of
+/// the dumped process, including its general purpose registers. Decoding the
+/// registers (e.g., finding the program counter) depends on the machine.
+#[derive(Copy, Clone, Debug)]
+pub struct PrStatus<'a> {
+    desc: &'a [u8],
+    pointer_size: usize,
+}
+
+impl<'a> PrStatus<'a> {
+    /// `pointer_size` is that of the core file, see `ElfFile::pointer_size`.
+    pub fn new(desc: &'a [u8], pointer_size: usize) -> Result<PrStatus<'a>, &'static str> {
+        let result = PrStatus {
+            desc,
+            pointer_size,
+        };
+        // The registers are followed by `pr_fpvalid`, padded to the pointer size.
+        if desc.len() < result.registers_offset() + pointer_size {
+            return Err("PRSTATUS note too short");
+        }
+        Ok(result)
+    }
+
+    /// The whole descriptor of the note.
+    pub fn desc(&self) -> &'a [u8] {
+        self.desc
+    }
+
+    /// The signal which caused the dump (`pr_cursig`).
+    pub fn signal(&self) -> u16 {
+        *read(&self.desc[12..14])
+    }
+
+    /// The thread ID (`pr_pid`).
+    pub fn pid(&self) -> u32 {
+        let offset = 16 + 2 * self.pointer_size;
+        *read(&self.desc[offset..offset + 4])
+    }
+
+    /// The offset of the register set (`pr_reg`) within the descriptor, after
+    /// the signal information and masks, the process IDs and four `timeval`s.
+    pub fn registers_offset(&self) -> usize {
+        16 + 2 * self.pointer_size + 16 + 4 * 2 * self.pointer_size
+    }
+
+    /// The raw bytes of the register set (`elf_gregset_t`) for the machine.
+    pub fn registers(&self) -> &'a [u8] {
+        &self.desc[self.registers_offset()..self.desc.len() - self.pointer_size]
+    }
+}
@@ -140,6 +140,24 @@ macro_rules! impls {
 impls!(P32);
 impls!(P64);
 
+// The tag and value of `entry`, widened so that both classes can be handled the
+// same way. `None` if the tag is invalid.
+pub(crate) fn widen<P>(entry: &Dynamic<P>) -> Option<(Tag<P64>, P64)>
+    where P: Copy + Into<P64>,
+          Tag_<P>: fmt::Debug
+{
+    Tag_(entry.tag.0.into()).as_tag().ok().map(|tag| (tag, entry.un.into()))
+}
+
+pub const TAG_GNU_HASH: u64 = 0x6ffffef5;
+
+/* Flag values used in the DT_FLAGS .dynamic entry.  */
+pub const FLAG_ORIGIN: u64 = 0x00000001;
+pub const FLAG_SYMBOLIC: u64 = 0x00000002;
+pub const FLAG_TEXTREL: u64 = 0x00000004;
+pub const FLAG_BIND_NOW: u64 = 0x00000008;
+pub const FLAG_STATIC_TLS: u64 = 0x00000010;
+
 /* Flag values used in the DT_FLAGS_1 .dynamic entry.  */
 pub const FLAG_1_NOW: u64 = 0x00000001;
 pub const FLAG_1_GLOBAL: u64 = 0x00000002;
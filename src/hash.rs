@@ -1,3 +1,4 @@
+use {ElfFile, try_read_array};
 use symbol_table::Entry;
 use zero::Pod;
 
@@ -25,17 +26,48 @@ pub fn hash(input: &str) -> u32 {
 }
 
 impl HashTable {
-    pub fn get_bucket(&self, index: u32) -> u32 {
-        assert!(index < self.bucket_count);
-        unsafe {
-            let ptr = (&self.first_bucket as *const u32).offset(index as isize);
-            *ptr
+    pub fn bucket_count(&self) -> u32 {
+        self.bucket_count
+    }
+
+    /// The number of chain entries, which is the number of symbols in the
+    /// symbol table.
+    pub fn chain_count(&self) -> u32 {
+        self.chain_count
+    }
+
+    /// The index in the symbol table of the first symbol in the given bucket,
+    /// or `None` if `index` is not less than `bucket_count`.
+    pub fn bucket(&self, index: u32) -> Option<u32> {
+        if index < self.bucket_count {
+            Some(self.word(index))
+        } else {
+            None
         }
     }
 
+    /// The index of the symbol after symbol `index` in its bucket's chain
+    /// (0 at the end of the chain), or `None` if `index` is not less than
+    /// `chain_count`.
+    pub fn chain(&self, index: u32) -> Option<u32> {
+        if index < self.chain_count {
+            Some(self.word(self.bucket_count + index))
+        } else {
+            None
+        }
+    }
+
+    pub fn get_bucket(&self, index: u32) -> u32 {
+        self.bucket(index).expect("Hash bucket index out of range")
+    }
+
     pub fn get_chain(&self, index: u32) -> u32 {
-        assert!(index < self.chain_count);
-        let index = self.bucket_count + index;
+        self.chain(index).expect("Hash chain index out of range")
+    }
+
+    // The buckets and chains follow the counts. `SectionHeader::get_data` checks
+    // that they are all within the section.
+    fn word(&self, index: u32) -> u32 {
         unsafe {
             let ptr = (&self.first_bucket as *const u32).offset(index as isize);
             *ptr
@@ -49,3 +81,179 @@ impl HashTable {
         unimplemented!();
     }
 }
+
+pub fn gnu_hash(input: &str) -> u32 {
+    let mut result: u32 = 5381;
+    for i in input.bytes() {
+        result = result.wrapping_mul(33).wrapping_add(i as u32);
+    }
+    result
+}
+
+/// A GNU-style hash table, as found in the .gnu.hash section.
+#[derive(Clone, Copy, Debug)]
+pub struct GnuHashTable<'a> {
+    symbol_offset: u32,
+    bloom_shift: u32,
+    // Bloom filter words are pointer-sized.
+    bloom: &'a [u8],
+    word_size: usize,
+    buckets: &'a [u32],
+    chains: &'a [u32],
+}
+
+impl<'a> GnuHashTable<'a> {
+    /// Parse the contents of a .gnu.hash section. `pointer_size` is the size of
+    /// the bloom filter words, see `ElfFile::pointer_size`.
+    pub fn new(data: &'a [u8], pointer_size: usize) -> Result<GnuHashTable<'a>, &'static str> {
+        if data.len() < 16 {
+            return Err("GNU hash table header truncated");
+        }
+        if pointer_size != 4 && pointer_size != 8 {
+            return Err("Invalid GNU hash bloom filter word size");
+        }
+        let header: &'a [u32] = try_read_array(&data[..16])?;
+        let (bucket_count, bloom_count) = (header[0] as usize, header[2] as usize);
+        if bucket_count == 0 {
+            return Err("GNU hash table has no buckets");
+        }
+        if bloom_count == 0 {
+            return Err("GNU hash table has no bloom filter");
+        }
+        if header[3] >= 32 {
+            return Err("GNU hash bloom shift out of range");
+        }
+
+        let bloom_end = bloom_count.checked_mul(pointer_size).and_then(|size| size.checked_add(16));
+        let buckets_end = bloom_end.and_then(|end| bucket_count.checked_mul(4)?.checked_add(end));
+        let (bloom_end, buckets_end) = match (bloom_end, buckets_end) {
+            (Some(bloom_end), Some(buckets_end)) if buckets_end <= data.len() => {
+                (bloom_end, buckets_end)
+            }
+            _ => return Err("GNU hash table truncated"),
+        };
+        let chains_end = buckets_end + (data.len() - buckets_end) / 4 * 4;
+
+        Ok(GnuHashTable {
+            symbol_offset: header[1],
+            bloom_shift: header[3],
+            bloom: &data[16..bloom_end],
+            word_size: pointer_size,
+            buckets: try_read_array(&data[bloom_end..buckets_end])?,
+            chains: try_read_array(&data[buckets_end..chains_end])?,
+        })
+    }
+
+    pub fn symbol_offset(&self) -> u32 {
+        self.symbol_offset
+    }
+
+    pub fn bucket_count(&self) -> u32 {
+        self.buckets.len() as u32
+    }
+
+    /// The number of symbols in the symbol table, which the table doesn't
+    /// record directly: one past the end of the chain for the last bucket.
+    /// `None` if that chain runs off the end of the table.
+    pub fn symbol_count(&self) -> Option<u32> {
+        let mut index = match self.buckets.iter().max() {
+            Some(&start) if start >= self.symbol_offset && start != 0 => start,
+            _ => return Some(self.symbol_offset),
+        };
+        loop {
+            match self.chains.get((index - self.symbol_offset) as usize) {
+                Some(chain) if chain & 1 != 0 => return index.checked_add(1),
+                Some(_) => index = index.checked_add(1)?,
+                None => return None,
+            }
+        }
+    }
+
+    /// Check that every symbol in `dynsym` from `symbol_offset` onwards is in
+    /// the bucket and chain matching its hash, and is accepted by the bloom
+    /// filter.
+    pub fn validate<E: Entry>(&self,
+                              elf_file: &ElfFile<'a>,
+                              dynsym: &'a [E])
+                              -> Result<(), &'static str> {
+        let symbol_count = dynsym.len() as u32;
+        if self.symbol_offset > symbol_count {
+            return Err("GNU hash symbol offset out of range");
+        }
+        if (self.chains.len() as u32) < symbol_count - self.symbol_offset {
+            return Err("GNU hash chain array too short");
+        }
+
+        let bucket_count = self.bucket_count();
+        for (bucket, &start) in self.buckets.iter().enumerate() {
+            if start == 0 {
+                continue;
+            }
+            if start < self.symbol_offset || start >= symbol_count {
+                return Err("GNU hash bucket out of range");
+            }
+            let hash = gnu_hash(dynsym[start as usize].get_name(elf_file)?);
+            if hash % bucket_count != bucket as u32 {
+                return Err("GNU hash bucket does not start with a symbol in that bucket");
+            }
+        }
+
+        let bits = self.word_size as u32 * 8;
+        let bloom_count = (self.bloom.len() / self.word_size) as u32;
+        let mut previous: Option<(u32, u32)> = None;
+        for index in self.symbol_offset..symbol_count {
+            let hash = gnu_hash(dynsym[index as usize].get_name(elf_file)?);
+            let bucket = hash % bucket_count;
+
+            if let Some((previous_bucket, previous_index)) = previous {
+                let chain_end = self.chains[(previous_index - self.symbol_offset) as usize] & 1;
+                if bucket == previous_bucket {
+                    if chain_end != 0 {
+                        return Err("GNU hash chain ends early");
+                    }
+                } else if bucket < previous_bucket {
+                    return Err("GNU hash symbols not sorted by bucket");
+                } else if chain_end == 0 {
+                    return Err("GNU hash chain does not end");
+                }
+            }
+            if previous.map(|(b, _)| b) != Some(bucket) && self.buckets[bucket as usize] != index {
+                return Err("GNU hash bucket does not start with its first symbol");
+            }
+            if self.chains[(index - self.symbol_offset) as usize] | 1 != hash | 1 {
+                return Err("GNU hash chain value does not match symbol hash");
+            }
+
+            let word = self.bloom_word((hash / bits) % bloom_count);
+            let mask = (1 << (hash % bits)) | (1 << ((hash >> self.bloom_shift) % bits));
+            if word & mask != mask {
+                return Err("GNU hash bloom filter rejects symbol");
+            }
+
+            previous = Some((bucket, index));
+        }
+
+        if let Some((_, last)) = previous {
+            if self.chains[(last - self.symbol_offset) as usize] & 1 == 0 {
+                return Err("GNU hash chain does not end");
+            }
+        }
+
+        Ok(())
+    }
+
+    fn bloom_word(&self, index: u32) -> u64 {
+        let start = index as usize * self.word_size;
+        let word = &self.bloom[start..start + self.word_size];
+        // The bloom filter need not be aligned for `u64`.
+        if self.word_size == 4 {
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(word);
+            u32::from_ne_bytes(bytes) as u64
+        } else {
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(word);
+            u64::from_ne_bytes(bytes)
+        }
+    }
+}
@@ -2,9 +2,13 @@ use core::fmt;
 use core::mem;
 
 use {P32, P64, ElfFile};
+use sections::SectionHeader_;
 use zero::{read, Pod};
 
 
+/// Read the ELF header from the start of `input`. On success, the class is
+/// `ThirtyTwo` or `SixtyFour` and `pt2` is of the matching width; the rest of
+/// the crate relies on this.
 pub fn parse_header<'a>(input: &'a [u8]) -> Result<Header<'a>, &'static str> {
     let size_pt1 = mem::size_of::<HeaderPt1>();
     if input.len() < size_pt1 {
@@ -16,18 +20,30 @@ pub fn parse_header<'a>(input: &'a [u8]) -> Result<Header<'a>, &'static str> {
         return Err("Did not find ELF magic number");
     }
 
-    let header_2 = match header_1.class() {
+    match header_1.data() {
+        Data::LittleEndian | Data::BigEndian => {}
+        Data::None | Data::Other(_) => return Err("Invalid ELF data encoding"),
+    }
+
+    let size_pt2 = match header_1.class() {
         Class::None | Class::Other(_) => return Err("Invalid ELF class"),
+        Class::ThirtyTwo => size_of::<HeaderPt2_<P32>>(),
+        Class::SixtyFour => size_of::<HeaderPt2_<P64>>(),
+    };
+    if input.len() < size_pt1 + size_pt2 {
+        return Err("File is shorter than ELF file header");
+    }
+
+    let header_2 = match header_1.class() {
         Class::ThirtyTwo => {
-            let header_2: &'a HeaderPt2_<P32> =
-                read(&input[size_pt1..size_pt1 + mem::size_of::<HeaderPt2_<P32>>()]);
+            let header_2: &'a HeaderPt2_<P32> = read(&input[size_pt1..size_pt1 + size_pt2]);
             HeaderPt2::Header32(header_2)
         }
         Class::SixtyFour => {
-            let header_2: &'a HeaderPt2_<P64> =
-                read(&input[size_pt1..size_pt1 + mem::size_of::<HeaderPt2_<P64>>()]);
+            let header_2: &'a HeaderPt2_<P64> = read(&input[size_pt1..size_pt1 + size_pt2]);
             HeaderPt2::Header64(header_2)
         }
+        Class::None | Class::Other(_) => unreachable!(),
     };
     Ok(Header {
         pt1: header_1,
@@ -45,6 +61,25 @@ pub struct Header<'a> {
 
 // TODO add Header::section_count, because if sh_count = 0, then the real count is in the first section.
 
+impl<'a> Header<'a> {
+    /// The file offset of the section header table (`e_shoff`).
+    pub fn section_header_offset(&self) -> u64 {
+        self.pt2.sh_offset()
+    }
+
+    /// The file offset of the program header table (`e_phoff`).
+    pub fn program_header_offset(&self) -> u64 {
+        self.pt2.ph_offset()
+    }
+
+    /// The size of the ELF header as given by the file (`e_ehsize`). This
+    /// should be 52 for 32-bit files and 64 for 64-bit files, which
+    /// `sanity_check` verifies.
+    pub fn header_size(&self) -> u16 {
+        self.pt2.header_size()
+    }
+}
+
 impl<'a> fmt::Display for Header<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         try!(writeln!(f, "ELF header:"));
@@ -118,6 +153,16 @@ impl<'a> HeaderPt2<'a> {
         }
     }
 
+    /// The object file type (`e_type`), e.g., `Executable` or `SharedObject`.
+    pub fn get_type(&self) -> Type {
+        self.type_().as_type()
+    }
+
+    /// The target architecture (`e_machine`).
+    pub fn get_machine(&self) -> Machine {
+        self.machine().as_machine()
+    }
+
     // TODO move to impl Header
     getter!(type_, Type_);
     getter!(machine, Machine_);
@@ -126,6 +171,7 @@ impl<'a> HeaderPt2<'a> {
     getter!(entry_point, u64);
     getter!(ph_offset, u64);
     getter!(sh_offset, u64);
+    getter!(flags, u32);
     getter!(ph_entry_size, u16);
     getter!(ph_count, u16);
     getter!(sh_entry_size, u16);
@@ -351,6 +397,9 @@ impl Type_ {
             2 => Type::Executable,
             3 => Type::SharedObject,
             4 => Type::Core,
+            x if (ET_LOOS..=ET_HIOS).contains(&x) => Type::OsSpecific(x),
+            // Values outside the reserved ranges are invalid, but have always
+            // been reported as processor specific.
             x => Type::ProcessorSpecific(x),
         }
     }
@@ -369,9 +418,15 @@ pub enum Type {
     Executable,
     SharedObject,
     Core,
-    ProcessorSpecific(u16), // TODO OsSpecific
+    OsSpecific(u16),
+    ProcessorSpecific(u16),
 }
 
+pub const ET_LOOS: u16 = 0xfe00;
+pub const ET_HIOS: u16 = 0xfeff;
+pub const ET_LOPROC: u16 = 0xff00;
+pub const ET_HIPROC: u16 = 0xffff;
+
 #[derive(Clone, Copy)]
 pub struct Machine_(u16);
 
@@ -383,11 +438,14 @@ impl Machine_ {
             0x03 => Machine::X86,
             0x08 => Machine::Mips,
             0x14 => Machine::PowerPC,
+            0x15 => Machine::PowerPC64,
+            0x16 => Machine::S390,
             0x28 => Machine::Arm,
             0x2A => Machine::SuperH,
             0x32 => Machine::Ia64,
             0x3E => Machine::X86_64,
             0xB7 => Machine::AArch64,
+            0xF3 => Machine::RiscV,
             other => Machine::Other(other),
         }
     }
@@ -400,21 +458,46 @@ impl fmt::Debug for Machine_ {
 }
 
 #[allow(non_camel_case_types)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Machine {
     None,
     Sparc,
     X86,
     Mips,
     PowerPC,
+    PowerPC64,
+    S390,
     Arm,
     SuperH,
     Ia64,
     X86_64,
     AArch64,
+    RiscV,
     Other(u16), // FIXME there are many, many more of these
 }
 
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Machine::None => "None",
+            Machine::Sparc => "SPARC",
+            Machine::X86 => "x86",
+            Machine::Mips => "MIPS",
+            Machine::PowerPC => "PowerPC",
+            Machine::PowerPC64 => "PowerPC64",
+            Machine::S390 => "S/390",
+            Machine::Arm => "ARM",
+            Machine::SuperH => "SuperH",
+            Machine::Ia64 => "IA-64",
+            Machine::X86_64 => "x86-64",
+            Machine::AArch64 => "AArch64",
+            Machine::RiscV => "RISC-V",
+            Machine::Other(machine) => return write!(f, "Other({:#x})", machine),
+        };
+        f.write_str(name)
+    }
+}
+
 // TODO any more constants that need to go in here?
 
 pub fn sanity_check(file: &ElfFile) -> Result<(), &'static str> {
@@ -439,7 +522,18 @@ pub fn sanity_check(file: &ElfFile) -> Result<(), &'static str> {
            file.input.len() as u64,
            "section header table out of range");
 
-    // TODO check that SectionHeader_ is the same size as sh_entry_size, depending on class
+    let word_size = file.pointer_size() as u64;
+    check!(file.header.program_header_offset().is_multiple_of(word_size),
+           "program header table is not word aligned");
+    check!(file.header.section_header_offset().is_multiple_of(word_size),
+           "section header table is not word aligned");
+
+    let sh_size = match file.header.pt1.class() {
+        Class::ThirtyTwo => size_of::<SectionHeader_<P32>>(),
+        _ => size_of::<SectionHeader_<P64>>(),
+    };
+    check!(pt2.sh_offset() == 0 || pt2.sh_entry_size() as usize == sh_size,
+           "sh_entry_size does not match size of section header");
 
     Ok(())
 }
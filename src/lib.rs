@@ -17,11 +17,19 @@ macro_rules! check {
     };
 }
 
-#[cfg(feature = "compression")]
+#[cfg(any(feature = "std", test))]
+#[cfg_attr(test, macro_use)]
 extern crate std;
 #[cfg(feature = "compression")]
 extern crate flate2;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "build-id")]
+extern crate sha1_smol;
+#[cfg(feature = "object-compat")]
+extern crate object;
+
 extern crate zero;
 
 pub mod header;
@@ -30,11 +38,32 @@ pub mod program;
 pub mod symbol_table;
 pub mod dynamic;
 pub mod hash;
+pub mod security;
+pub mod core_dump;
+#[cfg(feature = "std")]
+pub mod reader;
+#[cfg(feature = "object-compat")]
+pub mod object_compat;
+#[cfg(feature = "write")]
+pub mod write;
+#[cfg(feature = "alloc")]
+pub mod owned;
+
+#[cfg(feature = "compression")]
+use std::borrow::Cow;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "build-id")]
+use sha1_smol::Sha1;
 
-use header::Header;
-use sections::{SectionHeader, SectionIter};
-use program::{ProgramHeader, ProgramIter};
-use zero::{read, read_str};
+use header::{Header, HeaderPt1};
+use sections::{DwarfSection, SectionHeader, SectionIter};
+use program::{CoverageError, LoadSegment, ProgramHeader, ProgramIter, RelroRange, TlsInfo};
+use dynamic::Dynamic;
+use symbol_table::Entry;
+use zero::{read, read_array, read_str, Pod};
 
 pub type P32 = u32;
 pub type P64 = u64;
@@ -46,6 +75,11 @@ pub struct ElfFile<'a> {
 }
 
 impl<'a> ElfFile<'a> {
+    /// Parse the ELF header. This checks the magic number and the data
+    /// encoding, and that the class is 32 or 64-bit, so that code matching on
+    /// `class()` can treat `Class::None` and `Class::Other` as unreachable.
+    /// Files with a zero class byte are rejected here, with "Invalid ELF
+    /// class".
     pub fn new(input: &'a [u8]) -> Result<ElfFile<'a>, &'static str> {
         let header = try!(header::parse_header(input));
         Ok(ElfFile {
@@ -54,6 +88,42 @@ impl<'a> ElfFile<'a> {
         })
     }
 
+    /// Whether this is a 32 or 64-bit file; `new` checks that it is one of the
+    /// two. Code reading many entries (e.g., relocations) can match on this
+    /// once rather than for every entry.
+    pub fn class(&self) -> header::Class {
+        self.header.pt1.class()
+    }
+
+    /// The size in bytes of an address in this file: 4 for 32-bit files and 8
+    /// for 64-bit ones.
+    pub fn pointer_size(&self) -> usize {
+        match self.class() {
+            header::Class::ThirtyTwo => 4,
+            header::Class::SixtyFour => 8,
+            header::Class::None | header::Class::Other(_) => unreachable!(),
+        }
+    }
+
+    /// The virtual address at which execution starts (`e_entry`), or 0 if
+    /// there is none.
+    pub fn entry_point(&self) -> u64 {
+        self.header.pt2.entry_point()
+    }
+
+    /// The processor-specific flags (`e_flags`). How these are interpreted
+    /// depends on the machine, e.g., the EABI version and float ABI on ARM,
+    /// or the ISA and ABI on MIPS; many machines don't use any.
+    pub fn flags(&self) -> u32 {
+        self.header.pt2.flags()
+    }
+
+    /// The raw bytes of the ELF header (`e_ehsize` bytes for files which pass
+    /// `sanity_check`), e.g., to copy into a modified file.
+    pub fn header_bytes(&self) -> &'a [u8] {
+        &self.input[..size_of::<HeaderPt1>() + self.header.pt2.size()]
+    }
+
     pub fn section_header(&self, index: u16) -> Result<SectionHeader<'a>, &'static str> {
         sections::parse_section_header(self.input, self.header, index)
     }
@@ -65,6 +135,105 @@ impl<'a> ElfFile<'a> {
         }
     }
 
+    /// Like `section_iter`, but with the index of each section.
+    pub fn sections_enumerated<'b>(&'b self)
+                                   -> impl Iterator<Item = (u16, SectionHeader<'a>)> + 'b {
+        self.section_iter().enumerate().map(|(index, sect)| (index as u16, sect))
+    }
+
+    /// The index of `header` in the section header table, i.e., the `index`
+    /// for which `section_header(index)` returns it. `None` if `header` was not
+    /// read from this file's section header table.
+    pub fn section_index(&self, header: &SectionHeader<'a>) -> Option<u16> {
+        let address = match *header {
+            SectionHeader::Sh32(h) => h as *const _ as usize,
+            SectionHeader::Sh64(h) => h as *const _ as usize,
+        };
+        let pt2 = &self.header.pt2;
+        let table = (self.input.as_ptr() as usize).checked_add(pt2.sh_offset() as usize)?;
+        let entry_size = pt2.sh_entry_size() as usize;
+        let offset = address.wrapping_sub(table);
+        if entry_size == 0 || offset % entry_size != 0 ||
+           offset / entry_size >= pt2.sh_count() as usize {
+            return None;
+        }
+        Some((offset / entry_size) as u16)
+    }
+
+    /// Every section which has all the bits of `flag` set, e.g., `SHF_ALLOC`.
+    /// The null section is never included.
+    pub fn sections_with_flag<'b>(&'b self,
+                                  flag: u64)
+                                  -> impl Iterator<Item = SectionHeader<'a>> + 'b {
+        self.section_iter().filter(move |sect| {
+            sect.get_type() != Ok(sections::ShType::Null) && sect.flags() & flag == flag
+        })
+    }
+
+    /// Every section containing executable code (`SHF_EXECINSTR`), e.g.,
+    /// `.text`, `.plt`, `.init` and `.fini`.
+    pub fn executable_sections<'b>(&'b self) -> impl Iterator<Item = SectionHeader<'a>> + 'b {
+        self.sections_with_flag(sections::SHF_EXECINSTR)
+    }
+
+    /// The allocated section whose address range contains `addr`, if any.
+    /// Sections with an address of zero are ignored.
+    pub fn section_for_address(&self, addr: u64) -> Option<SectionHeader<'a>> {
+        self.sections_with_flag(sections::SHF_ALLOC).find(|sect| {
+            sect.address() != 0 && sect.address() <= addr && addr - sect.address() < sect.size()
+        })
+    }
+
+    /// The section whose contents in the file include `offset`, if any. If
+    /// sections overlap, the first in section header order is returned.
+    pub fn section_for_offset(&self, offset: u64) -> Option<SectionHeader<'a>> {
+        self.section_iter().find(|sect| {
+            match sect.get_type() {
+                Ok(sections::ShType::Null) | Ok(sections::ShType::NoBits) | Err(_) => false,
+                Ok(_) => sect.offset() <= offset && offset - sect.offset() < sect.size(),
+            }
+        })
+    }
+
+    /// The allocated sections whose address lies within the memory range of
+    /// the segment `ph`, in address order.
+    #[cfg(feature = "alloc")]
+    pub fn segment_sections(&self, ph: &ProgramHeader) -> Vec<SectionHeader<'a>> {
+        let mut result: Vec<_> = self.segment_sections_iter(ph).collect();
+        result.sort_by_key(|sect| sect.address());
+        result
+    }
+
+    /// Like `segment_sections`, but without allocating. The sections are in
+    /// section header order, which is not necessarily address order.
+    pub fn segment_sections_iter<'b>(&'b self,
+                                     ph: &ProgramHeader)
+                                     -> impl Iterator<Item = SectionHeader<'a>> + 'b {
+        let (start, size) = (ph.virtual_addr(), ph.mem_size());
+        self.sections_with_flag(sections::SHF_ALLOC).filter(move |sect| {
+            sect.address() >= start && sect.address() - start < size
+        })
+    }
+
+    /// The name and number of entries of every relocation (`Rel` or `Rela`)
+    /// section.
+    #[cfg(feature = "alloc")]
+    pub fn relocation_counts(&self) -> Vec<(&'a str, usize)> {
+        self.relocation_counts_iter().collect()
+    }
+
+    /// Like `relocation_counts`, but without allocating.
+    pub fn relocation_counts_iter<'b>(&'b self) -> impl Iterator<Item = (&'a str, usize)> + 'b {
+        self.section_iter()
+            .filter(|sect| {
+                matches!(sect.get_type(), Ok(sections::ShType::Rel) | Ok(sections::ShType::Rela))
+            })
+            .filter_map(move |sect| {
+                let count = sect.entry_count().unwrap_or(0) as usize;
+                sect.get_name(self).ok().map(|name| (name, count))
+            })
+    }
+
     pub fn program_header(&self, index: u16) -> Result<ProgramHeader<'a>, &'static str> {
         program::parse_program_header(self.input, self.header, index)
     }
@@ -76,21 +245,268 @@ impl<'a> ElfFile<'a> {
         }
     }
 
+    /// Every `Load` segment, with its file range checked against the file.
+    pub fn loadable_segments<'b>(&'b self) -> impl Iterator<Item = LoadSegment<'a>> + 'b {
+        self.program_iter()
+            .filter(|ph| ph.is_loadable())
+            .map(move |ph| LoadSegment::new(ph, self))
+    }
+
+    /// The path of the program interpreter (e.g., the dynamic linker) from the
+    /// `Interp` segment. The path must be null terminated within the segment's
+    /// file size; `None` if there is no such segment or it is malformed.
+    pub fn interpreter(&self) -> Option<&'a str> {
+        self.program_iter()
+            .find(|ph| ph.get_type() == Ok(program::Type::Interp))
+            .and_then(|ph| match ph.get_data(self) {
+                Ok(program::SegmentData::Interp(path)) => Some(path),
+                _ => None,
+            })
+    }
+
+    /// The layout of the thread-local storage template, or `None` if there is
+    /// no `Tls` segment.
+    pub fn tls_template(&self) -> Option<TlsInfo> {
+        self.program_iter().find(|ph| ph.get_type() == Ok(program::Type::Tls)).map(|ph| {
+            TlsInfo {
+                file_offset: ph.offset(),
+                file_size: ph.file_size(),
+                mem_size: ph.mem_size(),
+                align: ph.align(),
+            }
+        })
+    }
+
+    /// The descriptor of every note with the given owner (e.g., "GNU") and type,
+    /// e.g., each `NT_GNU_PROPERTY_TYPE_0` note. Notes are read from the note
+    /// sections, or from the note segments if there are no section headers.
+    pub fn note_descriptors<'b>(&'b self,
+                                owner: &'b str,
+                                note_type: u32)
+                                -> impl Iterator<Item = &'a [u8]> + 'b {
+        self.notes()
+            .filter(move |&(name, type_, _)| name == owner && type_ == note_type)
+            .map(|(_, _, desc)| desc)
+    }
+
+    /// The OS and minimum kernel version from the `NT_GNU_ABI_TAG` note, if
+    /// there is a well-formed one.
+    pub fn abi_tag(&self) -> Option<sections::AbiTag> {
+        self.note_descriptors("GNU", sections::NT_GNU_ABI_TAG)
+            .filter_map(|desc| sections::AbiTag::parse(desc).ok())
+            .next()
+    }
+
+    /// The JSON packaging metadata from the `NT_FDO_PACKAGING_METADATA` note
+    /// (in `.note.package`), if there is a well-formed one.
+    pub fn package_metadata(&self) -> Option<&'a str> {
+        self.note_descriptors("FDO", sections::NT_FDO_PACKAGING_METADATA)
+            .filter_map(|desc| read_str(desc).ok())
+            .next()
+    }
+
+    /// The properties of every `NT_GNU_PROPERTY_TYPE_0` note (normally there is
+    /// one, in `.note.gnu.property`).
+    pub fn gnu_properties<'b>(&'b self) -> impl Iterator<Item = sections::GnuProperty<'a>> + 'b {
+        let pointer_size = self.pointer_size();
+        self.note_descriptors("GNU", sections::NT_GNU_PROPERTY_TYPE_0)
+            .flat_map(move |desc| sections::gnu_properties(desc, pointer_size))
+    }
+
+    /// The x86 (IBT and SHSTK) and AArch64 (BTI and PAC) control-flow
+    /// protection features from the GNU property note. All are `false` if
+    /// there is no such note.
+    pub fn gnu_features(&self) -> sections::GnuFeatures {
+        let mut result = sections::GnuFeatures::default();
+        for property in self.gnu_properties() {
+            let features = property.feature_1_and().unwrap_or(0);
+            match property.type_ {
+                sections::GNU_PROPERTY_X86_FEATURE_1_AND => {
+                    result.ibt |= features & sections::GNU_PROPERTY_X86_FEATURE_1_IBT != 0;
+                    result.shstk |= features & sections::GNU_PROPERTY_X86_FEATURE_1_SHSTK != 0;
+                }
+                sections::GNU_PROPERTY_AARCH64_FEATURE_1_AND => {
+                    result.bti |= features & sections::GNU_PROPERTY_AARCH64_FEATURE_1_BTI != 0;
+                    result.pac |= features & sections::GNU_PROPERTY_AARCH64_FEATURE_1_PAC != 0;
+                }
+                _ => {}
+            }
+        }
+        result
+    }
+
+    /// The `NT_PRSTATUS` notes of a core dump, one per thread. Malformed notes
+    /// are skipped.
+    pub fn prstatus_notes<'b>(&'b self) -> impl Iterator<Item = core_dump::PrStatus<'a>> + 'b {
+        let pointer_size = self.pointer_size();
+        self.note_descriptors("CORE", core_dump::NT_PRSTATUS)
+            .filter_map(move |desc| core_dump::PrStatus::new(desc, pointer_size).ok())
+    }
+
+    /// Whether this is a position independent executable, as opposed to a
+    /// regular executable or a shared library: the type is `SharedObject` and
+    /// `DT_FLAGS_1` has `DF_1_PIE` set. `false` without a dynamic table.
+    pub fn is_pie(&self) -> bool {
+        self.header.pt2.get_type() == header::Type::SharedObject &&
+        self.get_dynamic_value(dynamic::Tag::Flags1)
+            .is_some_and(|flags| flags & dynamic::FLAG_1_PIE != 0)
+    }
+
+    /// Whether the `PT_GNU_STACK` segment asks for an executable stack (has
+    /// `PF_X` set). `None` if there is no such segment, in which case most
+    /// systems make the stack executable.
+    pub fn has_executable_stack(&self) -> Option<bool> {
+        self.program_iter()
+            .find(|ph| ph.get_type() == Ok(program::Type::OsSpecific(program::TYPE_GNU_STACK)))
+            .map(|ph| ph.flags().is_execute())
+    }
+
+    /// The address of the initialisation function given by `DT_INIT`, which a
+    /// loader calls before those in the init arrays (`init_functions`).
+    pub fn init_function(&self) -> Option<u64> {
+        self.get_dynamic_value(dynamic::Tag::Init)
+    }
+
+    /// The address of the termination function given by `DT_FINI`, which is
+    /// called after those in the fini arrays.
+    pub fn fini_function(&self) -> Option<u64> {
+        self.get_dynamic_value(dynamic::Tag::Fini)
+    }
+
+    /// The addresses of the initialisation functions, in the order in which
+    /// they should be called: those in `PreInitArray` sections, then those in
+    /// `InitArray` sections. A loader calls `DT_INIT` before these.
+    pub fn init_functions<'b>(&'b self) -> impl Iterator<Item = u64> + 'b {
+        self.function_addresses(sections::ShType::PreInitArray)
+            .chain(self.function_addresses(sections::ShType::InitArray))
+    }
+
+    /// The addresses of the termination functions in `FiniArray` sections, as
+    /// stored. These are called in reverse order.
+    pub fn fini_functions<'b>(&'b self) -> impl Iterator<Item = u64> + 'b {
+        self.function_addresses(sections::ShType::FiniArray)
+    }
+
+    /// The PLT relocations, which bind the GOT entries used by the PLT stubs
+    /// (lazily, unless `DT_BIND_NOW` is set). The table is given by `DT_JMPREL`,
+    /// `DT_PLTRELSZ` and `DT_PLTREL` (which says whether it is `Rel` or `Rela`).
+    /// `None` if those tags are missing, or the table is not backed by the file.
+    pub fn plt_relocations(&self) -> Option<impl Iterator<Item = sections::Relocation> + 'a> {
+        let address = self.get_dynamic_value(dynamic::Tag::JmpRel);
+        let size = self.get_dynamic_value(dynamic::Tag::PltRelSize);
+        // The tag of the table type, DT_RELA or DT_REL.
+        let rela = match self.get_dynamic_value(dynamic::Tag::PltRel) {
+            Some(7) => true,
+            Some(17) => false,
+            _ => return None,
+        };
+        match (address, size) {
+            (Some(address), Some(size)) => {
+                self.relocation_table(address, size, rela).map(|table| table.iter())
+            }
+            _ => None,
+        }
+    }
+
+    /// The dynamic relocations other than the PLT ones, from the tables given
+    /// by `DT_RELA`/`DT_RELASZ` and `DT_REL`/`DT_RELSZ` (files normally have
+    /// only one of the two). `None` if there is neither, or a table is not
+    /// backed by the file or has an unexpected entry size (`DT_RELAENT` or
+    /// `DT_RELENT`). Some linkers include the PLT relocations in `DT_RELASZ`.
+    ///
+    /// Most of these are usually `R_*_RELATIVE` relocations, which have no
+    /// symbol: the loader adds the load bias to the addend (stored at the
+    /// target for `Rel`), e.g., to fix up pointers in a PIE. `DT_RELACOUNT`
+    /// gives the number of them, as they are sorted first.
+    pub fn dynamic_relocations(&self) -> Option<impl Iterator<Item = sections::Relocation> + 'a> {
+        match (self.dynamic_relocation_table(true), self.dynamic_relocation_table(false)) {
+            (Ok(None), Ok(None)) | (Err(_), _) | (_, Err(_)) => None,
+            (Ok(rela), Ok(rel)) => Some(rela.into_iter().chain(rel).flat_map(|table| table.iter())),
+        }
+    }
+
+    /// The range which is made read-only after relocation, or `None` if there
+    /// is no `GnuRelro` segment.
+    pub fn relro(&self) -> Option<RelroRange> {
+        self.program_iter().find(|ph| ph.get_type() == Ok(program::Type::GnuRelro)).map(|ph| {
+            RelroRange {
+                virtual_addr: ph.virtual_addr(),
+                mem_size: ph.mem_size(),
+            }
+        })
+    }
+
+    /// Whether there is a `GnuRelro` segment, i.e., some data is made read-only
+    /// after relocation.
+    pub fn has_relro(&self) -> bool {
+        self.relro().is_some()
+    }
+
+    /// The hardening features the binary was built with (PIE, RELRO, etc.).
+    pub fn security_report(&self) -> security::SecurityReport {
+        security::SecurityReport::new(self)
+    }
+
+    /// The entries of the dynamic table up to `DT_NULL`, with decoded tags and
+    /// values widened to 64 bits so that both classes can be handled the same
+    /// way. The table is found through the Dynamic segment if there is no
+    /// Dynamic section. Entries with invalid tags are skipped.
+    pub fn dynamic_entries<'b>(&'b self) -> impl Iterator<Item = (dynamic::Tag<P64>, P64)> + 'b {
+        let (entries32, entries64) = self.dynamic_table();
+        entries32.iter()
+            .filter_map(dynamic::widen)
+            .chain(entries64.iter().filter_map(dynamic::widen))
+            .take_while(|(tag, _)| *tag != dynamic::Tag::Null)
+    }
+
+    /// The contents of the `.gcc_except_table` section, which holds the
+    /// language-specific data areas for exception handling, as
+    /// `SectionData::GccExceptTable`. It is a `ProgBits` section only
+    /// recognisable by its name, so `get_data` returns its data as `Undefined`.
+    pub fn gcc_except_table(&self) -> Option<sections::SectionData<'a>> {
+        self.find_section_by_name(".gcc_except_table")
+            .filter(|header| header.get_type() == Ok(sections::ShType::ProgBits))
+            .and_then(|header| header.try_raw_data(self).ok())
+            .map(sections::SectionData::GccExceptTable)
+    }
+
+    /// The section header string table, which holds the names of sections.
+    pub fn section_header_string_table(&self) -> Option<SectionHeader<'a>> {
+        match self.get_shstr_index() {
+            Ok(index) if index != sections::SHN_UNDEF as u32 => {
+                sections::parse_section_header_at(self.input, self.header, index).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// The contents of the section header string table, as a
+    /// `SectionData::StrArray`.
+    pub fn string_table(&self) -> Option<sections::SectionData<'a>> {
+        self.section_header_string_table().and_then(|header| header.get_data(self).ok())
+    }
+
+    /// The string at offset `index` in the section header string table. Like
+    /// `get_string` and `get_dyn_string`, this returns an error rather than
+    /// panicking if `index` is out of range or the table is not null
+    /// terminated.
     pub fn get_shstr(&self, index: u32) -> Result<&'a str, &'static str> {
-        self.get_shstr_table().and_then(|shstr_table| read_str(&shstr_table[(index as usize)..]))
+        self.get_shstr_table().and_then(|shstr_table| read_table_str(shstr_table, index))
     }
 
+    /// The string at offset `index` in `.strtab`, e.g., a symbol name.
     pub fn get_string(&self, index: u32) -> Result<&'a str, &'static str> {
         let header = try!(self.find_section_by_name(".strtab").ok_or("no .strtab section"));
         if try!(header.get_type()) != sections::ShType::StrTab {
             return Err("expected .strtab to be StrTab");
         }
-        read_str(&header.raw_data(self)[(index as usize)..])
+        read_table_str(self.string_table_data(header)?, index)
     }
 
+    /// The string at offset `index` in `.dynstr`, e.g., a dynamic symbol name.
     pub fn get_dyn_string(&self, index: u32) -> Result<&'a str, &'static str> {
         let header = try!(self.find_section_by_name(".dynstr").ok_or("no .dynstr section"));
-        read_str(&header.raw_data(self)[(index as usize)..])
+        read_table_str(self.string_table_data(header)?, index)
     }
 
     // This is really, stupidly slow. Not sure how to fix that, perhaps keeping
@@ -107,13 +523,518 @@ impl<'a> ElfFile<'a> {
         None
     }
 
+    /// Whether there is no symbol table (`SymTab`) section. A stripped file may
+    /// still have a dynamic symbol table.
+    pub fn is_stripped(&self) -> bool {
+        self.symbol_table_section().is_none()
+    }
+
+    /// Whether there is any DWARF section (`.debug_*`, or `.zdebug_*` if
+    /// compressed), i.e., any of `debug_sections`.
+    pub fn has_debug_info(&self) -> bool {
+        self.debug_sections().next().is_some()
+    }
+
+    /// Every DWARF section (`.debug_*` or `.zdebug_*`), e.g., to hand to a DWARF
+    /// parser.
+    pub fn debug_sections<'b>(&'b self)
+                              -> impl Iterator<Item = (DwarfSection, SectionHeader<'a>)> + 'b {
+        self.section_iter().filter_map(move |sect| {
+            sect.get_name(self)
+                .ok()
+                .and_then(DwarfSection::from_name)
+                .map(|dwarf| (dwarf, sect))
+        })
+    }
+
+    /// The contents of every named section keyed by name, decompressing any
+    /// compressed sections. `NoBits` sections map to an empty slice.
+    #[cfg(feature = "compression")]
+    pub fn section_map(&self) -> Result<HashMap<&'a str, Cow<'a, [u8]>>, &'static str> {
+        let mut result = HashMap::new();
+        for sect in self.section_iter() {
+            let data = match sect.get_type()? {
+                sections::ShType::Null => continue,
+                sections::ShType::NoBits => Cow::Borrowed(&[][..]),
+                _ => sect.decompressed_data(self)?,
+            };
+            let _ = result.insert(sect.get_name(self)?, data);
+        }
+        Ok(result)
+    }
+
+    /// Whether the function symbols in the symbol table appear in ascending
+    /// address order, so that they can be binary searched by address. Returns
+    /// false if there is no symbol table.
+    pub fn is_symtab_address_sorted(&self) -> bool {
+        fn is_sorted<E: Entry>(entries: &[E]) -> bool {
+            let mut last = 0;
+            for entry in entries {
+                if entry.get_type() != Ok(symbol_table::Type::Func) {
+                    continue;
+                }
+                if entry.value() < last {
+                    return false;
+                }
+                last = entry.value();
+            }
+            true
+        }
+
+        match self.symbol_table_section().and_then(|header| header.get_data(self).ok()) {
+            Some(sections::SectionData::SymbolTable32(entries)) => is_sorted(entries),
+            Some(sections::SectionData::SymbolTable64(entries)) => is_sorted(entries),
+            _ => false,
+        }
+    }
+
+    /// The function or object symbol whose `[value, value + size)` range
+    /// contains `addr`, with its name. If there is none, the closest zero-sized
+    /// symbol at or below `addr` is used instead. `.symtab` is searched before
+    /// `.dynsym`.
+    pub fn symbol_for_address(&self, addr: u64) -> Option<(&'a dyn Entry, &'a str)> {
+        fn search<'a, E: Entry>(entries: &'a [E], addr: u64) -> Option<&'a dyn Entry> {
+            let mut closest: Option<&'a E> = None;
+            for entry in entries {
+                match entry.get_type() {
+                    Ok(symbol_table::Type::Func) | Ok(symbol_table::Type::Object) => {}
+                    _ => continue,
+                }
+                if entry.shndx() == sections::SHN_UNDEF || entry.value() > addr {
+                    continue;
+                }
+                if addr - entry.value() < entry.size() {
+                    return Some(entry);
+                }
+                if entry.size() == 0 && closest.is_none_or(|c| c.value() < entry.value()) {
+                    closest = Some(entry);
+                }
+            }
+            closest.map(|entry| entry as &'a dyn Entry)
+        }
+
+        let dynsym = self.section_iter()
+                         .filter(|sect| sect.get_type() == Ok(sections::ShType::DynSym));
+        for header in self.symbol_table_section().into_iter().chain(dynsym) {
+            let found = match header.get_data(self) {
+                Ok(sections::SectionData::SymbolTable32(entries)) => search(entries, addr),
+                Ok(sections::SectionData::SymbolTable64(entries)) => search(entries, addr),
+                Ok(sections::SectionData::DynSymbolTable32(entries)) => search(entries, addr),
+                Ok(sections::SectionData::DynSymbolTable64(entries)) => search(entries, addr),
+                _ => None,
+            };
+            if let Some(entry) = found {
+                return entry.get_name(self).ok().map(|name| (entry, name));
+            }
+        }
+        None
+    }
+
+    /// The symbol named `name`, searching `.symtab` and then `.dynsym`. A
+    /// defined symbol is preferred over an undefined one (i.e., an import),
+    /// even if the latter comes first.
+    pub fn resolve_symbol(&self, name: &str) -> Option<&'a dyn Entry> {
+        let mut undefined = None;
+        let dynsym = self.section_iter()
+                         .filter(|sect| sect.get_type() == Ok(sections::ShType::DynSym));
+        for header in self.symbol_table_section().into_iter().chain(dynsym) {
+            let symbols = match header.get_data(self).ok().and_then(|data| data.symbols()) {
+                Some(symbols) => symbols,
+                None => continue,
+            };
+            for entry in symbols.iter().filter(|entry| entry.get_name(self) == Ok(name)) {
+                if entry.is_defined() {
+                    return Some(entry);
+                }
+                if undefined.is_none() {
+                    undefined = Some(entry);
+                }
+            }
+        }
+        undefined
+    }
+
+    /// The symbol named `name` in `.symtab`, or in `.dynsym` if there is no
+    /// `.symtab`, with its name as read from the table's string table. Unlike
+    /// `resolve_symbol`, only one table is searched. A defined symbol is
+    /// preferred over an undefined one.
+    pub fn lookup_symbol(&self, name: &str) -> Option<(&'a dyn Entry, &'a str)> {
+        let symbols = self.symbol_table_or_dynsym()
+                          .and_then(|header| header.get_data(self).ok())
+                          .and_then(|data| data.symbols())?;
+        let mut undefined = None;
+        for entry in symbols.iter() {
+            let entry_name = match entry.get_name(self) {
+                Ok(entry_name) if entry_name == name => entry_name,
+                _ => continue,
+            };
+            if entry.is_defined() {
+                return Some((entry, entry_name));
+            }
+            if undefined.is_none() {
+                undefined = Some((entry, entry_name));
+            }
+        }
+        undefined
+    }
+
+    /// The undefined symbols of the dynamic symbol table, i.e., those imported
+    /// from other objects. The null symbol at index 0 is skipped.
+    pub fn imported_symbols<'b>(&'b self) -> impl Iterator<Item = &'a dyn Entry> + 'b {
+        self.dynamic_symbols().skip(1).filter(|entry| entry.is_undefined())
+    }
+
+    /// The defined global and weak symbols of the dynamic symbol table, i.e.,
+    /// those which other objects can link against.
+    pub fn exported_symbols<'b>(&'b self) -> impl Iterator<Item = &'a dyn Entry> + 'b {
+        self.dynamic_symbols().filter(|entry| {
+            entry.is_defined() &&
+            matches!(entry.get_binding(),
+                     Ok(symbol_table::Binding::Global) | Ok(symbol_table::Binding::Weak))
+        })
+    }
+
+    /// A map from symbol names to their indices in `.symtab`, or `.dynsym` if
+    /// there is no `.symtab`, for repeated lookups by name. Symbols with empty
+    /// names are left out. If several symbols have the same name (e.g., local
+    /// symbols from different files), the last one wins.
+    #[cfg(feature = "std")]
+    pub fn build_symbol_index(&self) -> HashMap<&'a str, usize> {
+        let mut result = HashMap::new();
+        let symbols = self.symbol_table_or_dynsym()
+                          .and_then(|header| header.get_data(self).ok())
+                          .and_then(|data| data.symbols());
+        for (i, entry) in symbols.iter().flat_map(|symbols| symbols.iter()).enumerate() {
+            match entry.get_name(self) {
+                Ok(name) if !name.is_empty() => {
+                    let _ = result.insert(name, i);
+                }
+                _ => {}
+            }
+        }
+        result
+    }
+
+    /// The number of entries in `.symtab`, or `.dynsym` if there is no
+    /// `.symtab`. Without either (e.g., without section headers), the number of
+    /// dynamic symbols is found from the dynamic hash table. Zero if there is
+    /// no symbol table at all.
+    pub fn symbol_count(&self) -> usize {
+        let entry_size = match self.header.pt1.class() {
+            header::Class::ThirtyTwo => size_of::<symbol_table::Entry32>(),
+            header::Class::SixtyFour => size_of::<symbol_table::Entry64>(),
+            header::Class::None | header::Class::Other(_) => unreachable!(),
+        };
+        match self.symbol_table_or_dynsym() {
+            Some(table) => table.size() as usize / entry_size,
+            None => self.hash_table_symbol_count().unwrap_or(0),
+        }
+    }
+
+    /// Check that the dynamic string table (given by `DT_STRTAB` and
+    /// `DT_STRSZ`) lies within the file and starts and ends with a null byte.
+    /// Files without a dynamic string table trivially pass.
+    pub fn validate_dynamic_strtab(&self) -> Result<(), &'static str> {
+        let table = match self.dynamic_string_table()? {
+            Some(table) => table,
+            None => return Ok(()),
+        };
+        match (table.first(), table.last()) {
+            (Some(&0), Some(&0)) => Ok(()),
+            _ => Err("Dynamic string table does not start and end with a null byte"),
+        }
+    }
+
+    /// The shared object name (`DT_SONAME`), read from the dynamic string table
+    /// given by `DT_STRTAB`. `None` if there is no `DT_SONAME` entry or it
+    /// cannot be read.
+    pub fn soname(&self) -> Option<&'a str> {
+        self.get_dynamic_string(dynamic::Tag::SoName)
+    }
+
+    /// The library search path list (`DT_RUNPATH`), unsplit, i.e., with paths
+    /// separated by colons. Read like `soname`.
+    pub fn runpath(&self) -> Option<&'a str> {
+        self.get_dynamic_string(dynamic::Tag::RunPath)
+    }
+
+    /// The deprecated library search path list (`DT_RPATH`), which is ignored
+    /// by the dynamic linker if there is a `DT_RUNPATH`. Read like `runpath`.
+    pub fn rpath(&self) -> Option<&'a str> {
+        self.get_dynamic_string(dynamic::Tag::RPath)
+    }
+
+    /// Check that every allocated section of an executable or shared object
+    /// lies within the address range of some `Load` segment. On failure, the
+    /// error names the first section which is not covered.
+    pub fn validate_alloc_coverage(&self) -> Result<(), CoverageError<'a>> {
+        match self.header.pt2.get_type() {
+            header::Type::Executable | header::Type::SharedObject => {}
+            _ => return Ok(()),
+        }
+
+        for sect in self.section_iter() {
+            if sect.flags() & sections::SHF_ALLOC == 0 || sect.size() == 0 {
+                continue;
+            }
+            // .tbss takes no space in the memory image.
+            if sect.flags() & sections::SHF_TLS != 0 &&
+               sect.get_type().map_err(CoverageError::Malformed)? == sections::ShType::NoBits {
+                continue;
+            }
+
+            let start = sect.address();
+            let end = start.saturating_add(sect.size());
+            let covered = self.program_iter().any(|ph| {
+                ph.get_type() == Ok(program::Type::Load) && ph.virtual_addr() <= start &&
+                end <= ph.virtual_addr().saturating_add(ph.mem_size())
+            });
+            if !covered {
+                let name = sect.get_name(self).map_err(CoverageError::Malformed)?;
+                return Err(CoverageError::Uncovered(name));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Like `validate_alloc_coverage`, but with a fixed error message, as a
+    /// `&'static str` cannot hold the name of the uncovered section. Use
+    /// `validate_alloc_coverage` to find out which section it is.
+    pub fn check_section_segment_mapping(&self) -> Result<(), &'static str> {
+        self.validate_alloc_coverage().map_err(|error| match error {
+            CoverageError::Uncovered(_) => "Allocated section is not within any Load segment",
+            CoverageError::Malformed(error) => error,
+        })
+    }
+
+    // The string in the dynamic string table at the offset given by the value
+    // of the first dynamic entry with tag `tag`.
+    fn get_dynamic_string(&self, tag: dynamic::Tag<P64>) -> Option<&'a str> {
+        let index = match self.get_dynamic_value(tag) {
+            Some(index) if index <= u32::MAX as u64 => index as u32,
+            _ => return None,
+        };
+        match self.dynamic_string_table() {
+            Ok(Some(table)) => read_table_str(table, index).ok(),
+            _ => None,
+        }
+    }
+
+    // The dynamic string table, as given by DT_STRTAB and DT_STRSZ, or `None` if
+    // there is no DT_STRTAB entry.
+    fn dynamic_string_table(&self) -> Result<Option<&'a [u8]>, &'static str> {
+        let address = match self.get_dynamic_value(dynamic::Tag::StrTab) {
+            Some(address) => address,
+            None => return Ok(None),
+        };
+        let size = self.get_dynamic_value(dynamic::Tag::StrSize)
+                       .ok_or("DT_STRTAB without DT_STRSZ")?;
+        let start = self.virtual_addr_to_offset(address)
+                        .ok_or("Dynamic string table is not in a Load segment")?;
+        let end = start.checked_add(size).ok_or("Dynamic string table out of range")?;
+        if end > self.input.len() as u64 {
+            return Err("Dynamic string table out of range");
+        }
+        Ok(Some(&self.input[start as usize..end as usize]))
+    }
+
+    // Maps a virtual address to the file offset which backs it, using the Load
+    // segments.
+    fn virtual_addr_to_offset(&self, addr: u64) -> Option<u64> {
+        self.program_iter()
+            .find(|ph| {
+                ph.get_type() == Ok(program::Type::Load) && ph.virtual_addr() <= addr &&
+                addr - ph.virtual_addr() < ph.file_size()
+            })
+            .and_then(|ph| (addr - ph.virtual_addr()).checked_add(ph.offset()))
+    }
+
+    // The `Rela` or `Rel` table given by the dynamic table, or `None` if there
+    // is none.
+    fn dynamic_relocation_table(&self,
+                                rela: bool)
+                                -> Result<Option<sections::RelocationSlice<'a>>, &'static str> {
+        use dynamic::Tag;
+
+        let (address, size, entry_size) = if rela {
+            (Tag::Rela, Tag::RelaSize, Tag::RelaEnt)
+        } else {
+            (Tag::Rel, Tag::RelSize, Tag::RelEnt)
+        };
+        let (address, size) = match (self.get_dynamic_value(address),
+                                     self.get_dynamic_value(size)) {
+            (Some(address), Some(size)) => (address, size),
+            _ => return Ok(None),
+        };
+        let table = self.relocation_table(address, size, rela)
+                        .ok_or("Relocation table out of range")?;
+        let expected_size = match table {
+            sections::RelocationSlice::Rela32(_) => size_of::<sections::Rela<P32>>(),
+            sections::RelocationSlice::Rela64(_) => size_of::<sections::Rela<P64>>(),
+            sections::RelocationSlice::Rel32(_) => size_of::<sections::Rel<P32>>(),
+            sections::RelocationSlice::Rel64(_) => size_of::<sections::Rel<P64>>(),
+        };
+        match self.get_dynamic_value(entry_size) {
+            Some(entry_size) if entry_size != expected_size as u64 => {
+                Err("Unexpected relocation entry size")
+            }
+            _ => Ok(Some(table)),
+        }
+    }
+
+    // The relocation table which is `size` bytes at virtual address `address`,
+    // as given by the dynamic table.
+    fn relocation_table(&self,
+                        address: u64,
+                        size: u64,
+                        rela: bool)
+                        -> Option<sections::RelocationSlice<'a>> {
+        let data = self.virtual_addr_to_offset(address)
+            .and_then(|start| start.checked_add(size).map(|end| (start, end)))
+            .and_then(|(start, end)| self.input.get(start as usize..end as usize))?;
+        match (self.class(), rela) {
+            (header::Class::ThirtyTwo, true) => {
+                try_read_array(data).ok().map(sections::RelocationSlice::Rela32)
+            }
+            (header::Class::ThirtyTwo, false) => {
+                try_read_array(data).ok().map(sections::RelocationSlice::Rel32)
+            }
+            (_, true) => try_read_array(data).ok().map(sections::RelocationSlice::Rela64),
+            (_, false) => try_read_array(data).ok().map(sections::RelocationSlice::Rel64),
+        }
+    }
+
+    // The value of the first entry in the dynamic table with tag `tag`.
+    fn get_dynamic_value(&self, tag: dynamic::Tag<P64>) -> Option<P64> {
+        self.dynamic_entries().find(|(t, _)| *t == tag).map(|(_, value)| value)
+    }
+
+    // The entries of the Dynamic section, or of the Dynamic segment if there is
+    // no Dynamic section, e.g., in a file without section headers. Only one of
+    // the slices is non-empty, depending on the class of the file.
+    fn dynamic_table(&self) -> (&'a [Dynamic<P32>], &'a [Dynamic<P64>]) {
+        let section = self.section_iter().find(|s| s.get_type() == Ok(sections::ShType::Dynamic));
+        if let Some(section) = section {
+            return match section.get_data(self) {
+                Ok(sections::SectionData::Dynamic32(entries)) => (entries, &[]),
+                Ok(sections::SectionData::Dynamic64(entries)) => (&[], entries),
+                _ => (&[], &[]),
+            };
+        }
+
+        let segment = self.program_iter().find(|ph| ph.get_type() == Ok(program::Type::Dynamic));
+        match segment.map(|ph| ph.get_data(self)) {
+            Some(Ok(program::SegmentData::Dynamic32(entries))) => (entries, &[]),
+            Some(Ok(program::SegmentData::Dynamic64(entries))) => (&[], entries),
+            _ => (&[], &[]),
+        }
+    }
+
+    // The addresses in every section of type `typ`, which should be one of the
+    // function array types.
+    fn function_addresses<'b>(&'b self,
+                              typ: sections::ShType)
+                              -> impl Iterator<Item = u64> + 'b {
+        self.section_iter()
+            .filter(move |sect| sect.get_type() == Ok(typ))
+            .filter_map(move |sect| sect.get_data(self).ok())
+            .filter_map(|data| data.function_addresses())
+            .flatten()
+    }
+
+    // The name, type and descriptor of every note in the note sections, or in
+    // the note segments if there are no section headers. A note segment
+    // usually covers several note sections, so using both would yield
+    // duplicates.
+    fn notes<'b>(&'b self) -> impl Iterator<Item = (&'a str, u32, &'a [u8])> + 'b {
+        let has_sections = self.header.pt2.sh_count() > 0;
+        let sections = self.section_iter()
+            .filter(|sect| sect.get_type() == Ok(sections::ShType::Note))
+            .filter_map(move |sect| sect.get_data(self).ok().and_then(|data| data.notes()));
+        let segments = self.program_iter()
+            .filter(move |ph| !has_sections && ph.get_type() == Ok(program::Type::Note))
+            .filter_map(move |ph| ph.get_data(self).ok().and_then(|data| data.notes()));
+        sections.flatten().chain(segments.flatten())
+    }
+
+    // The number of dynamic symbols, according to the DT_HASH or DT_GNU_HASH
+    // table.
+    fn hash_table_symbol_count(&self) -> Option<usize> {
+        if let Some(address) = self.get_dynamic_value(dynamic::Tag::Hash) {
+            let offset = self.virtual_addr_to_offset(address)? as usize;
+            let size = size_of::<hash::HashTable>();
+            let data = self.input.get(offset..)?.get(..size)?;
+            let table: &[hash::HashTable] = try_read_array(data).ok()?;
+            return Some(table[0].chain_count() as usize);
+        }
+
+        let address = self.get_dynamic_value(dynamic::Tag::OsSpecific(dynamic::TAG_GNU_HASH));
+        let offset = match address.and_then(|a| self.virtual_addr_to_offset(a)) {
+            Some(offset) => offset as usize,
+            None => return None,
+        };
+        self.input
+            .get(offset..)
+            .and_then(|data| hash::GnuHashTable::new(data, self.pointer_size()).ok())
+            .and_then(|table| table.symbol_count())
+            .map(|count| count as usize)
+    }
+
+    fn symbol_table_section(&self) -> Option<SectionHeader<'a>> {
+        self.section_iter().find(|sect| sect.get_type() == Ok(sections::ShType::SymTab))
+    }
+
+    // The entries of the first `DynSym` section, if any.
+    fn dynamic_symbols<'b>(&'b self) -> impl Iterator<Item = &'a dyn Entry> + 'b {
+        self.section_iter()
+            .find(|sect| sect.get_type() == Ok(sections::ShType::DynSym))
+            .and_then(|header| header.get_data(self).ok())
+            .and_then(|data| data.symbols())
+            .into_iter()
+            .flat_map(|symbols| symbols.iter())
+    }
+
+    fn symbol_table_or_dynsym(&self) -> Option<SectionHeader<'a>> {
+        self.symbol_table_section().or_else(|| {
+            self.section_iter().find(|sect| sect.get_type() == Ok(sections::ShType::DynSym))
+        })
+    }
+
+    fn get_shstr_index(&self) -> Result<u32, &'static str> {
+        match self.header.pt2.sh_str_index() {
+            // The real index doesn't fit, it is in the link field of section 0.
+            sections::SHN_XINDEX => self.section_header(0).map(|h| h.link()),
+            index => Ok(index as u32),
+        }
+    }
+
     fn get_shstr_table(&self) -> Result<&'a [u8], &'static str> {
         // TODO cache this?
-        let header = self.section_header(self.header.pt2.sh_str_index());
-        header.map(|h| &self.input[(h.offset() as usize)..])
+        let index = self.get_shstr_index()?;
+        let header = sections::parse_section_header_at(self.input, self.header, index)?;
+        self.string_table_data(header)
+    }
+
+    // The contents of a string table section, which must be null terminated.
+    // This must not use `get_data`, which may look up section names.
+    fn string_table_data(&self, header: SectionHeader<'a>) -> Result<&'a [u8], &'static str> {
+        if header.get_type() != Ok(sections::ShType::StrTab) {
+            return Err("Expected a string table");
+        }
+        let data = header.try_raw_data(self)?;
+        if data.last().is_some_and(|&b| b != 0) {
+            return Err("String table is not null terminated");
+        }
+        Ok(data)
     }
 }
 
+// Reads the string starting at `index` in a string table.
+fn read_table_str(table: &[u8], index: u32) -> Result<&str, &'static str> {
+    read_str(table.get(index as usize..).ok_or("String table index out of range")?)
+}
+
 /// A trait for things that are common ELF conventions but not part of the ELF
 /// specification.
 pub trait Extensions<'a> {
@@ -124,29 +1045,27 @@ pub trait Extensions<'a> {
     /// Parse and return the value of the .gnu_debuglink section, if it
     /// exists and is well-formed.
     fn get_gnu_debuglink(&self) -> Option<(&'a str, u32)>;
+
+    /// Parse and return the JSON packaging metadata in the .note.package
+    /// section, if it exists and is well-formed. See
+    /// `ElfFile::package_metadata`.
+    fn get_package_metadata(&self) -> Option<&'a str>;
+
+    /// The language-specific data area (LSDA) at `address`, as given by the
+    /// LSDA pointer of an FDE in .eh_frame. The data runs to the end of the
+    /// .gcc_except_table section, it is up to the caller to parse it.
+    fn get_lsda(&self, address: u64) -> Option<&'a [u8]>;
 }
 
 impl<'a> Extensions<'a> for ElfFile<'a> {
     fn get_gnu_buildid(&self) -> Option<&'a [u8]> {
         self.find_section_by_name(".note.gnu.build-id")
             .and_then(|header| header.get_data(self).ok())
-            .and_then(|data| match data {
-                // Handle Note32 if it's ever implemented!
-                sections::SectionData::Note64(header, data) => Some((header, data)),
-                _ => None,
-            })
-            .and_then(|(header, data)| {
-                // Check for NT_GNU_BUILD_ID
-                if header.type_() != 0x3 {
-                    return None;
-                }
-
-                if header.name(data) != "GNU" {
-                    return None;
-                }
-
-                Some(header.desc(data))
+            .and_then(|data| data.notes())
+            .and_then(|mut notes| {
+                notes.find(|&(name, type_, _)| type_ == sections::NT_GNU_BUILD_ID && name == "GNU")
             })
+            .map(|(_, _, desc)| desc)
     }
 
     fn get_gnu_debuglink(&self) -> Option<(&'a str, u32)> {
@@ -163,17 +1082,122 @@ impl<'a> Extensions<'a> for ElfFile<'a> {
                 Some((file, checksum))
             })
     }
-}
 
-#[cfg(test)]
-#[macro_use]
-extern crate std;
+    fn get_package_metadata(&self) -> Option<&'a str> {
+        self.package_metadata()
+    }
+
+    fn get_lsda(&self, address: u64) -> Option<&'a [u8]> {
+        let start = self.find_section_by_name(".gcc_except_table")?.address();
+        match self.gcc_except_table() {
+            Some(sections::SectionData::GccExceptTable(data)) => {
+                address.checked_sub(start).and_then(|offset| data.get(offset as usize..))
+            }
+            _ => None,
+        }
+    }
+}
+
+// Like `zero::read_array`, but returns an error rather than panicking if the
+// input is not a whole number of `T`s or is not aligned for `T`.
+pub(crate) fn try_read_array<T: Pod>(input: &[u8]) -> Result<&[T], &'static str> {
+    let size = size_of::<T>();
+    if size == 0 || !input.len().is_multiple_of(size) {
+        return Err("Data is not a whole number of entries");
+    }
+    if !(input.as_ptr() as usize).is_multiple_of(align_of::<T>()) {
+        return Err("Data is misaligned");
+    }
+    Ok(read_array(input))
+}
+
+/// Recompute the build-id of a file linked with `ld --build-id=sha1`, to check
+/// against the one from `get_gnu_buildid`. Like ld, this is the SHA-1 of:
+///
+/// 1. the ELF header, with `e_phoff` and `e_shoff` zeroed;
+/// 2. each program header, in order;
+/// 3. for each section in order, including the null section, its header with
+///    `sh_offset` zeroed, followed by its contents.
+///
+/// The contents of the `.note.gnu.build-id` section are hashed as zeros (ld
+/// fills them in afterwards). The contents of `NoBits` sections, symbol tables
+/// (`SymTab` and `SymTabShIndex`), the symbol string table and the section
+/// header string table are skipped, since ld writes those separately. Any
+/// change to the file after linking, such as stripping, changes the result.
+#[cfg(feature = "build-id")]
+pub fn compute_build_id_sha1(elf_file: &ElfFile) -> Result<[u8; 20], &'static str> {
+    use sections::ShType;
+
+    let input = elf_file.input;
+    let pt2 = &elf_file.header.pt2;
+    let (e_offsets, sh_offset) = match elf_file.header.pt1.class() {
+        header::Class::ThirtyTwo => (28..36, 16..20),
+        header::Class::SixtyFour => (32..48, 24..32),
+        header::Class::None | header::Class::Other(_) => unreachable!(),
+    };
+    let mut hasher = Sha1::new();
+
+    let mut buf = [0; 64];
+    let header_size = pt2.header_size() as usize;
+    if header_size > buf.len() || header_size > input.len() {
+        return Err("header_size out of range");
+    }
+    buf[..header_size].copy_from_slice(&input[..header_size]);
+    for b in &mut buf[e_offsets] {
+        *b = 0;
+    }
+    hasher.update(&buf[..header_size]);
+
+    for index in 0..pt2.ph_count() as u64 {
+        let entry_size = pt2.ph_entry_size() as u64;
+        let range = index.checked_mul(entry_size)
+                         .and_then(|offset| offset.checked_add(pt2.ph_offset()))
+                         .and_then(|start| start.checked_add(entry_size).map(|end| (start, end)));
+        let (start, end) = match range {
+            Some((start, end)) if end <= input.len() as u64 => (start, end),
+            _ => return Err("Program header out of range"),
+        };
+        hasher.update(&input[start as usize..end as usize]);
+    }
+
+    let entry_size = pt2.sh_entry_size() as usize;
+    if entry_size > buf.len() {
+        return Err("sh_entry_size out of range");
+    }
+    let shstr_index = elf_file.get_shstr_index()?;
+    let strtab_index = elf_file.symbol_table_section().map(|symtab| symtab.link());
+    for (index, sect) in elf_file.section_iter().enumerate() {
+        let start = pt2.sh_offset() as usize + index * entry_size;
+        buf[..entry_size].copy_from_slice(&input[start..start + entry_size]);
+        for b in &mut buf[sh_offset.clone()] {
+            *b = 0;
+        }
+        hasher.update(&buf[..entry_size]);
+
+        match sect.get_type()? {
+            ShType::Null | ShType::NoBits | ShType::SymTab | ShType::SymTabShIndex => continue,
+            _ if index as u32 == shstr_index || Some(index as u32) == strtab_index => continue,
+            _ => {}
+        }
+        let data = sect.try_raw_data(elf_file)?;
+        if sect.get_name(elf_file) == Ok(".note.gnu.build-id") {
+            for chunk in data.chunks(buf.len()) {
+                hasher.update(&[0; 64][..chunk.len()]);
+            }
+        } else {
+            hasher.update(data);
+        }
+    }
+
+    Ok(hasher.digest().bytes())
+}
 
 #[cfg(test)]
 mod test {
     use std::prelude::v1::*;
 
     use std::mem;
+    use std::slice;
 
     use super::*;
     use header::{HeaderPt1, HeaderPt2_};
@@ -193,6 +1217,288 @@ mod test {
         header
     }
 
+    // A section for `mk_elf64`; the remaining fields default to zero.
+    struct TestSection {
+        name: &'static str,
+        type_: u32,
+        flags: u64,
+        address: u64,
+        link: u32,
+        info: u32,
+        entry_size: u64,
+        data: Vec<u8>,
+    }
+
+    impl TestSection {
+        fn new(name: &'static str, type_: u32, flags: u64, data: Vec<u8>) -> TestSection {
+            TestSection {
+                name: name,
+                type_: type_,
+                flags: flags,
+                address: 0,
+                link: 0,
+                info: 0,
+                entry_size: 0,
+                data: data,
+            }
+        }
+    }
+
+    fn put(buf: &mut [u8], offset: usize, bytes: &[u8]) {
+        buf[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    // A little-endian, 64-bit executable with the null section, then
+    // `sections`, then .shstrtab. A single Load segment maps the whole file at
+    // address 0, and allocated sections without an explicit address are given
+    // their file offset. All the fixtures in these tests are little-endian, as
+    // `mk_elf_header` says.
+    fn mk_elf64(sections: &[TestSection]) -> Vec<u8> {
+        fn align(buf: &mut Vec<u8>) {
+            while buf.len() % 8 != 0 {
+                buf.push(0);
+            }
+        }
+
+        let mut shstrtab = vec![0];
+        for sect in sections {
+            shstrtab.extend_from_slice(sect.name.as_bytes());
+            shstrtab.push(0);
+        }
+        shstrtab.extend_from_slice(b".shstrtab\0");
+        let shstrtab = TestSection::new(".shstrtab", 3, 0, shstrtab);
+
+        let mut elf = mk_elf_header(2);
+        elf.resize(64 + 56, 0);
+        let mut offsets = vec![];
+        for sect in sections.iter().chain(Some(&shstrtab)) {
+            align(&mut elf);
+            offsets.push(elf.len() as u64);
+            elf.extend_from_slice(&sect.data);
+        }
+        align(&mut elf);
+
+        let sh_offset = elf.len();
+        elf.resize(sh_offset + 64, 0);
+        let mut name = 1;
+        for (sect, &offset) in sections.iter().chain(Some(&shstrtab)).zip(&offsets) {
+            elf.extend_from_slice(&(name as u32).to_le_bytes());
+            elf.extend_from_slice(&sect.type_.to_le_bytes());
+            elf.extend_from_slice(&sect.flags.to_le_bytes());
+            let address = match sect.address {
+                0 if sect.flags & sections::SHF_ALLOC != 0 => offset,
+                address => address,
+            };
+            elf.extend_from_slice(&address.to_le_bytes());
+            elf.extend_from_slice(&offset.to_le_bytes());
+            elf.extend_from_slice(&(sect.data.len() as u64).to_le_bytes());
+            elf.extend_from_slice(&sect.link.to_le_bytes());
+            elf.extend_from_slice(&sect.info.to_le_bytes());
+            elf.extend_from_slice(&0u64.to_le_bytes());
+            elf.extend_from_slice(&sect.entry_size.to_le_bytes());
+            name += sect.name.len() + 1;
+        }
+
+        let size = (elf.len() as u64).to_le_bytes();
+        put(&mut elf, 64, &[1, 0, 0, 0, 7, 0, 0, 0]);
+        put(&mut elf, 64 + 32, &size);
+        put(&mut elf, 64 + 40, &size);
+
+        // Executable, x86-64, version 1.
+        let count = offsets.len() as u16 + 1;
+        put(&mut elf, 16, &[2, 0, 62, 0, 1, 0, 0, 0]);
+        put(&mut elf, 32, &64u64.to_le_bytes());
+        put(&mut elf, 40, &(sh_offset as u64).to_le_bytes());
+        put(&mut elf, 52, &[64, 0, 56, 0, 1, 0, 64, 0]);
+        put(&mut elf, 60, &count.to_le_bytes());
+        put(&mut elf, 62, &(count - 1).to_le_bytes());
+        elf
+    }
+
+    // A 64-bit symbol table entry.
+    fn mk_symbol64(name: u32, type_: u8, shndx: u16, value: u64, size: u64) -> Vec<u8> {
+        let mut entry = name.to_le_bytes().to_vec();
+        entry.extend_from_slice(&[type_, 0]);
+        entry.extend_from_slice(&shndx.to_le_bytes());
+        entry.extend_from_slice(&value.to_le_bytes());
+        entry.extend_from_slice(&size.to_le_bytes());
+        entry
+    }
+
+    // A single ABI tag note (name "GNU", type 1), followed by `rest`.
+    fn mk_abi_tag_note(rest: &[u32]) -> Vec<u32> {
+        let mut note = vec![4, 16, 1, u32::from_le_bytes(*b"GNU\0"), 0, 3, 2, 0];
+        note.extend_from_slice(rest);
+        note
+    }
+
+    // A file with a .dynstr section holding `dynstr`, and a .dynamic section
+    // holding DT_STRTAB, DT_STRSZ, then the tag and value pairs in `rest`, then
+    // DT_NULL.
+    fn mk_dynamic_elf(dynstr: &[u8], rest: &[u64]) -> Vec<u8> {
+        let mut dynamic = vec![];
+        let head = [5, 0, 10, dynstr.len() as u64];
+        for &word in head.iter().chain(rest).chain(&[0, 0]) {
+            dynamic.extend_from_slice(&word.to_le_bytes());
+        }
+        let mut elf = mk_elf64(&[TestSection::new(".dynstr",
+                                                  3,
+                                                  sections::SHF_ALLOC,
+                                                  dynstr.to_vec()),
+                                 TestSection::new(".dynamic", 6, sections::SHF_ALLOC, dynamic)]);
+        let (dynstr, dynamic) = {
+            let file = ElfFile::new(&elf).unwrap();
+            (file.find_section_by_name(".dynstr").unwrap().address(),
+             file.find_section_by_name(".dynamic").unwrap().offset() as usize)
+        };
+        put(&mut elf, dynamic + 8, &dynstr.to_le_bytes());
+        elf
+    }
+
+    // The little-endian bytes of `words`, e.g., for note and hash sections.
+    fn le_bytes(words: &[u32]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_le_bytes().to_vec()).collect()
+    }
+
+    #[test]
+    fn note_desc_as() {
+        let note = mk_abi_tag_note(&[]);
+        let bytes = le_bytes(&note);
+        let header: &sections::NoteHeader = read(&bytes[..12]);
+        assert_eq!(header.desc_as::<u32>(&bytes[12..]), Ok(&[0, 3, 2, 0][..]));
+        assert!(header.desc_as::<u64>(&bytes[12..bytes.len() - 4]).is_err());
+
+        #[derive(Debug)]
+        struct Three([u8; 3]);
+        unsafe impl zero::Pod for Three {}
+        assert!(header.desc_as::<Three>(&bytes[12..]).is_err());
+    }
+
+    #[test]
+    fn note_descriptors() {
+        // Two NT_GNU_PROPERTY_TYPE_0 notes, each with one x86 feature property.
+        let property = |features| vec![4, 16, 5, u32::from_le_bytes(*b"GNU\0"),
+                                       0xc0000002, 4, features, 0];
+        let mut notes = property(1);
+        notes.extend(mk_abi_tag_note(&[]));
+        let elf = mk_elf64(&[TestSection::new(".note.a", 7, 0, le_bytes(&notes)),
+                             TestSection::new(".note.b", 7, 0, le_bytes(&property(2)))]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let descs: Vec<_> = elf.note_descriptors("GNU", 5).collect();
+        assert_eq!(descs, [le_bytes(&[0xc0000002, 4, 1, 0]), le_bytes(&[0xc0000002, 4, 2, 0])]);
+        assert_eq!(elf.note_descriptors("GNU", 1).count(), 1);
+        assert_eq!(elf.note_descriptors("FDO", 5).count(), 0);
+    }
+
+    #[test]
+    fn gnu_properties() {
+        // An x86 ISA level property followed by the feature property, and a
+        // truncated property.
+        let gnu = u32::from_le_bytes(*b"GNU\0");
+        let note = [4, 40, 5, gnu, 0xc0008002, 4, 1, 0, 0xc0000002, 4, 3, 0, 0xc0000000, 8];
+        let elf = mk_elf64(&[TestSection::new(".note.gnu.property",
+                                              7,
+                                              0,
+                                              le_bytes(&note))]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let properties: Vec<_> = elf.gnu_properties().collect();
+        assert_eq!(properties.len(), 2);
+        assert_eq!(properties[0].type_, 0xc0008002);
+        assert_eq!(properties[0].feature_1_and(), None);
+        assert_eq!(properties[1].feature_1_and(), Some(3));
+        assert_eq!(elf.gnu_features(),
+                   sections::GnuFeatures {
+                       ibt: true,
+                       shstk: true,
+                       ..Default::default()
+                   });
+
+        let note = [4, 16, 5, gnu, 0xc0000000, 4, 3, 0];
+        let elf = mk_elf64(&[TestSection::new(".note.gnu.property",
+                                              7,
+                                              0,
+                                              le_bytes(&note))]);
+        let features = ElfFile::new(&elf).unwrap().gnu_features();
+        assert!(features.bti && features.pac && !features.ibt);
+        assert_eq!(ElfFile::new(&mk_elf64(&[])).unwrap().gnu_features(), Default::default());
+
+        let desc = le_bytes(&[0xc0000002, 4, 3, 0]);
+        assert_eq!(sections::gnu_properties(&desc, 8).count(), 1);
+        assert_eq!(sections::gnu_properties(&desc, 3).count(), 0);
+        let desc = le_bytes(&[0xc0000002, 0xffff_fffc, 3, 0]);
+        assert_eq!(sections::gnu_properties(&desc, 8).count(), 0);
+    }
+
+    #[test]
+    fn note_type() {
+        let note_type = |words: &[u32]| {
+            let bytes = le_bytes(words);
+            let header: &sections::NoteHeader = read(&bytes[..12]);
+            (header.type_(), header.get_gnu_type())
+        };
+        assert_eq!(note_type(&mk_abi_tag_note(&[])),
+                   (sections::NT_GNU_ABI_TAG, sections::GnuNoteType::AbiTag));
+        let note = [4, 20, 3, u32::from_le_bytes(*b"GNU\0")];
+        assert_eq!(note_type(&note).1, sections::GnuNoteType::BuildId);
+        assert_eq!(note_type(&[4, 0, 0x100, 0]).1, sections::GnuNoteType::Other(0x100));
+    }
+
+    #[test]
+    fn abi_tag() {
+        let note = mk_abi_tag_note(&[]);
+        let elf = mk_elf64(&[TestSection::new(".note.ABI-tag", 7, 0, le_bytes(&note))]);
+        let elf = ElfFile::new(&elf).unwrap();
+        assert_eq!(elf.abi_tag(),
+                   Some(sections::AbiTag {
+                       os: sections::ELF_NOTE_OS_LINUX,
+                       major: 3,
+                       minor: 2,
+                       subminor: 0,
+                   }));
+        let bytes = le_bytes(&note);
+        assert_eq!(sections::AbiTag::parse(&bytes[16..28]),
+                   Err("ABI tag note too short"));
+        assert_eq!(ElfFile::new(&mk_elf64(&[])).unwrap().abi_tag(), None);
+    }
+
+    #[test]
+    fn prstatus_notes() {
+        // An x86-64 `struct elf_prstatus` is 336 bytes, with the registers at
+        // 112.
+        let mut note = vec![5, 336, core_dump::NT_PRSTATUS];
+        note.extend_from_slice(&[u32::from_le_bytes(*b"CORE"), 0]);
+        let mut desc = vec![0u32; 84];
+        desc[3] = 11;
+        desc[8] = 1234;
+        desc[28] = 0xdead;
+        note.extend(desc);
+        let elf = mk_elf64(&[TestSection::new("note0", 7, 0, le_bytes(&note))]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let statuses: Vec<_> = elf.prstatus_notes().collect();
+        assert_eq!(statuses.len(), 1);
+        let status = statuses[0];
+        assert_eq!((status.signal(), status.pid()), (11, 1234));
+        assert_eq!(status.registers_offset(), 112);
+        assert_eq!(status.registers().len(), 216);
+        assert_eq!(&status.registers()[..4], &0xdeadu32.to_le_bytes());
+        assert!(core_dump::PrStatus::new(&status.desc()[..112], 8).is_err());
+    }
+
+    #[test]
+    fn malformed_note_name() {
+        // A note named "GNUX" with no terminator, followed by a good note.
+        let mut notes = vec![4, 4, 1, u32::from_le_bytes(*b"GNUX"), 0];
+        notes.extend(mk_abi_tag_note(&[]));
+        let bytes = le_bytes(&notes);
+        let header: &sections::NoteHeader = read(&bytes[..12]);
+        assert_eq!(header.try_name(&bytes[12..]), Err("Note name is not null terminated"));
+
+        let notes: Vec<_> = sections::NoteIter::new(header, &bytes[12..]).collect();
+        assert_eq!(notes, [("GNU", 1, &le_bytes(&[0, 3, 2, 0])[..])]);
+    }
+
     #[test]
     fn interpret_class() {
         assert!(ElfFile::new(&mk_elf_header(0)).is_err());
@@ -200,4 +1506,1692 @@ mod test {
         assert!(ElfFile::new(&mk_elf_header(2)).is_ok());
         assert!(ElfFile::new(&mk_elf_header(42u8)).is_err());
     }
+
+    #[test]
+    fn new_checks_header() {
+        let mut header = mk_elf_header(2);
+        assert!(ElfFile::new(&header).is_ok());
+        assert_eq!(ElfFile::new(&header[..40]).err(),
+                   Some("File is shorter than ELF file header"));
+        header[5] = 0;
+        assert_eq!(ElfFile::new(&header).err(), Some("Invalid ELF data encoding"));
+        header[4] = 0;
+        header[5] = 1;
+        assert_eq!(ElfFile::new(&header).err(), Some("Invalid ELF class"));
+        header[0] = 0;
+        assert_eq!(ElfFile::new(&header).err(), Some("Did not find ELF magic number"));
+
+        // A whole file, which would otherwise reach the class matches in
+        // `get_data` and friends.
+        let mut elf = mk_elf64(&[TestSection::new(".symtab", 2, 0, vec![0; 24])]);
+        elf[4] = 0;
+        assert_eq!(ElfFile::new(&elf).err(), Some("Invalid ELF class"));
+    }
+
+    #[test]
+    fn pointer_size() {
+        assert_eq!(ElfFile::new(&mk_elf_header(1)).unwrap().pointer_size(), 4);
+        assert_eq!(ElfFile::new(&mk_elf_header(2)).unwrap().pointer_size(), 8);
+    }
+
+    #[test]
+    fn sections_with_flag() {
+        use sections::{SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE};
+
+        let elf = mk_elf64(&[TestSection::new(".init", 1, SHF_ALLOC | SHF_EXECINSTR, vec![0xc3]),
+                             TestSection::new(".data", 1, SHF_ALLOC | SHF_WRITE, vec![0; 8]),
+                             TestSection::new(".text", 1, SHF_ALLOC | SHF_EXECINSTR, vec![0x90]),
+                             TestSection::new(".comment", 1, 0, b"GCC\0".to_vec())]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let names = |flag| {
+            elf.sections_with_flag(flag).map(|s| s.get_name(&elf).unwrap()).collect::<Vec<_>>()
+        };
+        assert_eq!(names(SHF_EXECINSTR), [".init", ".text"]);
+        assert_eq!(names(SHF_ALLOC | SHF_WRITE), [".data"]);
+        assert_eq!(names(0), [".init", ".data", ".text", ".comment", ".shstrtab"]);
+    }
+
+    #[test]
+    fn executable_sections() {
+        use sections::{SHF_ALLOC, SHF_EXECINSTR};
+
+        let elf = mk_elf64(&[TestSection::new(".plt", 1, SHF_ALLOC | SHF_EXECINSTR, vec![0; 16]),
+                             TestSection::new(".rodata", 1, SHF_ALLOC, vec![0; 4]),
+                             TestSection::new(".text", 1, SHF_ALLOC | SHF_EXECINSTR, vec![0x90])]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let names: Vec<_> = elf.executable_sections().map(|s| s.get_name(&elf).unwrap()).collect();
+        assert_eq!(names, [".plt", ".text"]);
+    }
+
+    #[test]
+    fn section_for_address() {
+        use sections::{SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE};
+
+        let elf = mk_elf64(&[TestSection::new(".text", 1, SHF_ALLOC | SHF_EXECINSTR, vec![0; 8]),
+                             TestSection::new(".comment", 1, 0, vec![0; 8]),
+                             TestSection::new(".data", 1, SHF_ALLOC | SHF_WRITE, vec![0; 8])]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let data = elf.find_section_by_name(".data").unwrap();
+        let name = |addr| elf.section_for_address(addr).map(|s| s.get_name(&elf).unwrap());
+        assert_eq!(name(data.address()), Some(".data"));
+        assert_eq!(name(data.address() + 7), Some(".data"));
+        assert_eq!(name(data.address() + 8), None);
+        assert_eq!(name(data.address() - 1), None);
+        assert_eq!(name(0), None);
+    }
+
+    #[test]
+    fn alloc_coverage() {
+        use sections::{SHF_ALLOC, SHF_TLS, SHF_WRITE};
+
+        let text = || TestSection::new(".text", 1, SHF_ALLOC, vec![0; 8]);
+        // .tbss takes no space in the memory image, so needs no segment.
+        let tbss = || {
+            TestSection {
+                address: 0x10000,
+                ..TestSection::new(".tbss", 8, SHF_ALLOC | SHF_WRITE | SHF_TLS, vec![])
+            }
+        };
+        let bss = TestSection {
+            address: 0x10000,
+            ..TestSection::new(".bss", 8, SHF_ALLOC | SHF_WRITE, vec![0; 8])
+        };
+        let comment = TestSection {
+            address: 0x10000,
+            ..TestSection::new(".comment", 1, 0, vec![])
+        };
+
+        let elf = mk_elf64(&[text(), tbss(), comment]);
+        let elf = ElfFile::new(&elf).unwrap();
+        assert_eq!(elf.validate_alloc_coverage(), Ok(()));
+        assert_eq!(elf.check_section_segment_mapping(), Ok(()));
+
+        let elf = mk_elf64(&[text(), bss, tbss()]);
+        let elf = ElfFile::new(&elf).unwrap();
+        assert_eq!(elf.validate_alloc_coverage(), Err(CoverageError::Uncovered(".bss")));
+        assert_eq!(elf.check_section_segment_mapping(),
+                   Err("Allocated section is not within any Load segment"));
+    }
+
+    #[test]
+    fn section_for_offset() {
+        use sections::{SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE};
+
+        let elf = mk_elf64(&[TestSection::new(".text", 1, SHF_ALLOC | SHF_EXECINSTR, vec![0; 8]),
+                             TestSection::new(".bss", 8, SHF_ALLOC | SHF_WRITE, vec![0; 8]),
+                             TestSection::new(".comment", 1, 0, vec![0; 8])]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let offset = |name| elf.find_section_by_name(name).unwrap().offset();
+        let name = |offset| elf.section_for_offset(offset).map(|s| s.get_name(&elf).unwrap());
+        assert_eq!(name(offset(".text")), Some(".text"));
+        assert_eq!(name(offset(".text") + 7), Some(".text"));
+        assert_eq!(name(offset(".comment")), Some(".comment"));
+        // .bss has no contents in the file.
+        assert_eq!(name(offset(".bss")), None);
+        assert_eq!(name(0), None);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn segment_sections() {
+        use sections::{SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE};
+
+        let code = |name| TestSection::new(name, 1, SHF_ALLOC | SHF_EXECINSTR, vec![0; 8]);
+        let mut elf = mk_elf64(&[TestSection::new(".rodata", 1, SHF_ALLOC, vec![0; 8]),
+                                 code(".init"),
+                                 code(".plt"),
+                                 code(".text"),
+                                 code(".fini"),
+                                 TestSection::new(".data", 1, SHF_ALLOC | SHF_WRITE, vec![0; 8])]);
+        let (start, end) = {
+            let file = ElfFile::new(&elf).unwrap();
+            let fini = file.find_section_by_name(".fini").unwrap();
+            (file.find_section_by_name(".init").unwrap().address(), fini.address() + fini.size())
+        };
+        // Shrink the Load segment to just the code.
+        elf[64 + 16..64 + 24].copy_from_slice(&start.to_le_bytes());
+        elf[64 + 40..64 + 48].copy_from_slice(&(end - start).to_le_bytes());
+
+        let elf = ElfFile::new(&elf).unwrap();
+        let ph = elf.program_header(0).unwrap();
+        let names: Vec<_> = elf.segment_sections(&ph)
+                               .iter()
+                               .map(|s| s.get_name(&elf).unwrap())
+                               .collect();
+        assert_eq!(names, [".init", ".plt", ".text", ".fini"]);
+    }
+
+    #[test]
+    fn section_header_string_table() {
+        let elf = mk_elf64(&[TestSection::new(".text", 1, sections::SHF_ALLOC, vec![0x90]),
+                             TestSection::new(".comment", 1, 0, b"GCC\0".to_vec())]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let header = elf.section_header_string_table().unwrap();
+        assert_eq!(header.get_name(&elf), Ok(".shstrtab"));
+
+        let names: Vec<_> = elf.string_table().unwrap().strings().unwrap().collect();
+        let section_names: Vec<_> = elf.section_iter()
+                                       .skip(1)
+                                       .map(|s| s.get_name(&elf).unwrap())
+                                       .collect();
+        assert_eq!(names, [".text", ".comment", ".shstrtab"]);
+        assert_eq!(names, section_names);
+    }
+
+    #[test]
+    fn nobits_raw_data() {
+        use sections::{SHF_ALLOC, SHF_WRITE};
+
+        let bss = TestSection::new(".bss", 8, SHF_ALLOC | SHF_WRITE, vec![0xff; 0x40]);
+        let mut elf = mk_elf64(&[bss]);
+        // Make .bss (section 1) much larger than the file, as it would be in
+        // practice.
+        let bss_header = ElfFile::new(&elf).unwrap().header.section_header_offset() as usize + 64;
+        elf[bss_header + 32..bss_header + 40].copy_from_slice(&0x100000u64.to_le_bytes());
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let bss = elf.find_section_by_name(".bss").unwrap();
+        assert_eq!(bss.size(), 0x100000);
+        assert_eq!(bss.raw_data(&elf), &[]);
+        assert_eq!(bss.try_raw_data(&elf), Ok(&[][..]));
+    }
+
+    #[test]
+    fn comment_strings() {
+        let comment = b"GCC: (GNU) 12.2.0\0clang version 16.0.6\0".to_vec();
+        let elf = mk_elf64(&[TestSection::new(".comment", 1, 0, comment),
+                             TestSection::new(".dynamic", 6, 0, vec![0; 16])]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let data = elf.find_section_by_name(".comment").unwrap().get_data(&elf).unwrap();
+        assert!(data.strings().is_err());
+        let strings: Vec<_> = data.as_strings().unwrap().collect();
+        assert_eq!(strings, ["GCC: (GNU) 12.2.0", "clang version 16.0.6"]);
+
+        let data = elf.find_section_by_name(".dynamic").unwrap().get_data(&elf).unwrap();
+        assert!(data.as_strings().is_none());
+    }
+
+    #[test]
+    fn section_index() {
+        let elf = mk_elf64(&[TestSection::new(".text", 1, 0, vec![0; 4]),
+                             TestSection::new(".data", 1, 0, vec![0; 4])]);
+        let file = ElfFile::new(&elf).unwrap();
+
+        for (i, sect) in file.sections_enumerated() {
+            assert_eq!(file.section_index(&sect), Some(i));
+            assert_eq!(file.section_header(i).unwrap().offset(), sect.offset());
+        }
+        assert_eq!(file.sections_enumerated().count(), 4);
+        let data = file.find_section_by_name(".data").unwrap();
+        assert_eq!(file.section_index(&data), Some(2));
+
+        let copy = elf.clone();
+        let other = ElfFile::new(&copy).unwrap();
+        assert_eq!(other.section_index(&data), None);
+
+        // A section header table offset which overflows when added to the
+        // input's address.
+        let mut copy = elf.clone();
+        put(&mut copy, 40, &(u64::MAX - 8).to_le_bytes());
+        let other = ElfFile::new(&copy).unwrap();
+        assert_eq!(other.section_index(&data), None);
+    }
+
+    #[test]
+    fn raw_header_bytes() {
+        let elf = mk_elf64(&[TestSection::new(".text", 1, 0, vec![0; 4])]);
+        let file = ElfFile::new(&elf).unwrap();
+        assert_eq!(file.header_bytes(), &elf[..64]);
+
+        let pt2 = &file.header.pt2;
+        let text = file.find_section_by_name(".text").unwrap();
+        let start = pt2.sh_offset() as usize + pt2.sh_entry_size() as usize;
+        assert_eq!(text.as_bytes().len(), pt2.sh_entry_size() as usize);
+        assert_eq!(text.as_bytes(), &elf[start..start + 64]);
+
+        let mut bad = elf.clone();
+        bad[58] = 32; // e_shentsize
+        assert_eq!(ElfFile::new(&bad).and_then(|file| header::sanity_check(&file)),
+                   Err("sh_entry_size does not match size of section header"));
+    }
+
+    #[test]
+    fn validate_entry_size() {
+        let table = |name, type_, entry_size| {
+            TestSection { entry_size: entry_size, ..TestSection::new(name, type_, 0, vec![0; 48]) }
+        };
+        let symtab = table(".symtab", 2, 24);
+        // Rela entries are 24 bytes in a 64-bit file.
+        let rela = table(".rela.text", 4, 16);
+        let elf = mk_elf64(&[symtab, rela, TestSection::new(".text", 1, 0, vec![0; 4])]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let symtab = elf.find_section_by_name(".symtab").unwrap();
+        assert_eq!(symtab.validate_entry_size(&elf), Ok(()));
+        assert_eq!(sections::sanity_check(symtab, &elf), Ok(()));
+        let rela = elf.find_section_by_name(".rela.text").unwrap();
+        assert_eq!(rela.validate_entry_size(&elf),
+                   Err("Section entry size does not match its type"));
+        assert_eq!(sections::sanity_check(rela, &elf),
+                   Err("Section entry size does not match its type"));
+        let text = elf.find_section_by_name(".text").unwrap();
+        assert_eq!(text.validate_entry_size(&elf), Ok(()));
+
+        // An unspecified entry size, and 8-byte hash entries on 64-bit s390.
+        let init_array = table(".init_array", 14, 0);
+        let mut elf = mk_elf64(&[init_array, table(".hash", 5, 8)]);
+        put(&mut elf, 18, &22u16.to_le_bytes());
+        let elf = ElfFile::new(&elf).unwrap();
+        for sect in elf.section_iter().skip(1) {
+            assert_eq!(sections::sanity_check(sect, &elf), Ok(()));
+        }
+    }
+
+    #[test]
+    fn unterminated_string_table() {
+        let elf = mk_elf64(&[TestSection::new(".strtab", 3, 0, b"\0main\0puts".to_vec())]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let strtab = elf.find_section_by_name(".strtab").unwrap();
+        assert_eq!(strtab.get_data(&elf).err(), Some("String table is not null terminated"));
+        assert_eq!(sections::sanity_check(strtab, &elf),
+                   Err("String table is not null terminated"));
+        assert_eq!(elf.get_string(1), Err("String table is not null terminated"));
+
+        let elf = mk_elf64(&[TestSection::new(".strtab", 3, 0, b"\0main\0".to_vec())]);
+        let elf = ElfFile::new(&elf).unwrap();
+        assert_eq!(elf.get_string(1), Ok("main"));
+        assert_eq!(elf.get_string(100), Err("String table index out of range"));
+        assert_eq!(elf.get_shstr(1000), Err("String table index out of range"));
+    }
+
+    #[test]
+    fn get_string_errors() {
+        // `get_string` and `get_dyn_string` are the checked lookups; there is
+        // no separate `try_` variant.
+        let elf = mk_elf64(&[TestSection::new(".strtab", 3, 0, b"\0main\0".to_vec()),
+                             TestSection::new(".dynstr", 3, 0, b"\0puts".to_vec())]);
+        let elf = ElfFile::new(&elf).unwrap();
+        assert_eq!(elf.get_string(5), Ok(""));
+        assert!(elf.get_string(6).is_err());
+        assert_eq!(elf.get_string(u32::MAX), Err("String table index out of range"));
+        assert_eq!(elf.get_dyn_string(1), Err("String table is not null terminated"));
+        assert_eq!(elf.get_dyn_string(u32::MAX), Err("String table is not null terminated"));
+    }
+
+    #[test]
+    fn section_symbol_name() {
+        // The null symbol, then a local STT_SECTION symbol for section 1.
+        let mut symtab = vec![0; 24];
+        symtab.extend_from_slice(&[0, 0, 0, 0, 3, 0, 1, 0]);
+        symtab.extend_from_slice(&[0; 16]);
+
+        let elf = mk_elf64(&[TestSection::new(".text", 1, sections::SHF_ALLOC, vec![0x90]),
+                             TestSection::new(".strtab", 3, 0, vec![0]),
+                             TestSection::new(".symtab", 2, 0, symtab)]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let symtab = elf.find_section_by_name(".symtab").unwrap();
+        match symtab.get_data(&elf) {
+            Ok(sections::SectionData::SymbolTable64(entries)) => {
+                assert_eq!(entries[0].get_name(&elf), Ok(""));
+                assert_eq!(entries[1].get_name(&elf), Ok(".text"));
+            }
+            other => panic!("unexpected section data {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn relocation_counts() {
+        let rela = |name, count: usize| {
+            TestSection { entry_size: 24, ..TestSection::new(name, 4, 0, vec![0; 24 * count]) }
+        };
+        let elf = mk_elf64(&[rela(".rela.dyn", 3),
+                             TestSection::new(".text", 1, sections::SHF_ALLOC, vec![0x90]),
+                             rela(".rela.plt", 5)]);
+        let elf = ElfFile::new(&elf).unwrap();
+        assert_eq!(elf.relocation_counts(), [(".rela.dyn", 3), (".rela.plt", 5)]);
+        assert!(elf.relocation_counts_iter().eq(elf.relocation_counts()));
+    }
+
+    #[test]
+    fn file_type() {
+        let mut elf = mk_elf64(&[]);
+        assert_eq!(ElfFile::new(&elf).unwrap().header.pt2.get_type(), header::Type::Executable);
+        for &(value, type_) in &[(1, header::Type::Relocatable),
+                                 (3, header::Type::SharedObject),
+                                 (0xfe01, header::Type::OsSpecific(0xfe01)),
+                                 (0xff01, header::Type::ProcessorSpecific(0xff01))] {
+            elf[16..18].copy_from_slice(&(value as u16).to_le_bytes());
+            assert_eq!(ElfFile::new(&elf).unwrap().header.pt2.get_type(), type_);
+        }
+    }
+
+    #[test]
+    fn entry_point_and_flags() {
+        let mut elf = mk_elf64(&[]);
+        assert_eq!(ElfFile::new(&elf).unwrap().flags(), 0);
+        elf[24..32].copy_from_slice(&0x401000u64.to_le_bytes());
+        // EF_ARM_EABI_VER5 | EF_ARM_ABI_FLOAT_HARD
+        elf[48..52].copy_from_slice(&0x05000400u32.to_le_bytes());
+        let file = ElfFile::new(&elf).unwrap();
+        assert_eq!(file.entry_point(), 0x401000);
+        assert_eq!(file.flags(), 0x05000400);
+    }
+
+    #[test]
+    fn machine() {
+        use header::Machine;
+
+        let mut elf = mk_elf64(&[]);
+        for &(value, machine, name) in &[(0x3e, Machine::X86_64, "x86-64"),
+                                         (0xb7, Machine::AArch64, "AArch64"),
+                                         (0xf3, Machine::RiscV, "RISC-V"),
+                                         (0x15, Machine::PowerPC64, "PowerPC64"),
+                                         (0x16, Machine::S390, "S/390"),
+                                         (0x1234, Machine::Other(0x1234), "Other(0x1234)")] {
+            elf[18..20].copy_from_slice(&(value as u16).to_le_bytes());
+            let file = ElfFile::new(&elf).unwrap();
+            assert_eq!(file.header.pt2.get_machine(), machine);
+            assert_eq!(format!("{}", machine), name);
+        }
+    }
+
+    #[test]
+    fn header_size() {
+        let mut elf = mk_elf64(&[]);
+        assert_eq!(ElfFile::new(&elf).unwrap().header.header_size(), 64);
+        assert_eq!(header::sanity_check(&ElfFile::new(&elf).unwrap()), Ok(()));
+
+        elf[52..54].copy_from_slice(&52u16.to_le_bytes());
+        let file = ElfFile::new(&elf).unwrap();
+        assert_eq!(file.header.header_size(), 52);
+        assert_eq!(header::sanity_check(&file),
+                   Err("header_size does not match size of header"));
+    }
+
+    #[test]
+    fn misaligned_section_headers() {
+        let mut elf = mk_elf64(&[]);
+        assert_eq!(header::sanity_check(&ElfFile::new(&elf).unwrap()), Ok(()));
+
+        let sh_offset = ElfFile::new(&elf).unwrap().header.section_header_offset();
+        elf.insert(sh_offset as usize, 0);
+        elf[40..48].copy_from_slice(&(sh_offset + 1).to_le_bytes());
+        let file = ElfFile::new(&elf).unwrap();
+        assert_eq!(file.header.section_header_offset(), sh_offset + 1);
+        assert_eq!(header::sanity_check(&file),
+                   Err("section header table is not word aligned"));
+    }
+
+    #[test]
+    fn progbits_section_header_string_table() {
+        let mut elf = mk_elf64(&[TestSection::new(".gcc_except_table", 1, 0, vec![0])]);
+        // Make .shstrtab (section 2) ProgBits rather than StrTab.
+        let sh_offset = ElfFile::new(&elf).unwrap().header.section_header_offset() as usize;
+        put(&mut elf, sh_offset + 2 * 64 + 4, &1u32.to_le_bytes());
+        let elf = ElfFile::new(&elf).unwrap();
+        let table = elf.section_header(1).unwrap();
+        assert_eq!(table.get_name(&elf), Err("Expected a string table"));
+        assert!(elf.gcc_except_table().is_none());
+        assert!(table.get_data(&elf).is_ok());
+    }
+
+    #[test]
+    fn linked_section_in_reserved_range() {
+        let mut text = TestSection::new(".text", 1, sections::SHF_ALLOC, vec![0x90]);
+        text.link = 0xff10;
+        let mut elf = mk_elf64(&[text]);
+        // A corrupt section count which lets the link into the reserved range.
+        put(&mut elf, 60, &0xff20u16.to_le_bytes());
+        let elf = ElfFile::new(&elf).unwrap();
+        assert!(elf.section_header(1).unwrap().linked_section(&elf).is_none());
+    }
+
+    #[test]
+    fn extended_shstr_index() {
+        let mut elf = mk_elf64(&[TestSection::new(".text", 1, sections::SHF_ALLOC, vec![0x90])]);
+        let sh_offset = ElfFile::new(&elf).unwrap().header.section_header_offset() as usize;
+        // Move the string table index (2) into section 0's link field.
+        elf[62..64].copy_from_slice(&sections::SHN_XINDEX.to_le_bytes());
+        elf[sh_offset + 40..sh_offset + 44].copy_from_slice(&2u32.to_le_bytes());
+
+        let elf = ElfFile::new(&elf).unwrap();
+        assert_eq!(elf.section_header(1).unwrap().get_name(&elf), Ok(".text"));
+        assert!(elf.find_section_by_name(".shstrtab").is_some());
+    }
+
+    #[test]
+    fn group_members() {
+        use sections::{GRP_COMDAT, SHF_ALLOC, SHF_GROUP};
+
+        let mk_group = |members: &[u32]| {
+            let mut words = vec![GRP_COMDAT as u32];
+            words.extend_from_slice(members);
+            TestSection::new(".group", 17, 0, le_bytes(&words))
+        };
+        let text = TestSection::new(".text.f", 1, SHF_ALLOC | SHF_GROUP, vec![0x90]);
+        let data = TestSection::new(".data.f", 1, SHF_ALLOC | SHF_GROUP, vec![0; 8]);
+
+        let elf = mk_elf64(&[mk_group(&[2, 3]), text, data]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let group = elf.find_section_by_name(".group").unwrap().get_data(&elf).unwrap();
+        let names: Vec<_> = group.group_members(&elf)
+                                 .unwrap()
+                                 .map(|sect| sect.get_name(&elf).unwrap())
+                                 .collect();
+        assert_eq!(names, [".text.f", ".data.f"]);
+
+        let elf = mk_elf64(&[mk_group(&[2, 100])]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let group = elf.find_section_by_name(".group").unwrap().get_data(&elf).unwrap();
+        assert!(group.group_members(&elf).is_none());
+        let strtab = elf.section_header_string_table().unwrap().get_data(&elf).unwrap();
+        assert!(strtab.group_members(&elf).is_none());
+    }
+
+    #[test]
+    fn comdat_group() {
+        use sections::{SHF_ALLOC, SHF_GROUP};
+
+        let mut symtab = vec![0; 24];
+        symtab.extend(mk_symbol64(1, 2, 4, 0, 1));
+        let mk_group = |flags: u32| {
+            TestSection {
+                link: 2,
+                info: 1,
+                ..TestSection::new(".group", 17, 0, le_bytes(&[flags, 4]))
+            }
+        };
+        let mk_elf = |group| {
+            mk_elf64(&[TestSection::new(".strtab", 3, 0, b"\0f\0".to_vec()),
+                       TestSection { link: 1, ..TestSection::new(".symtab", 2, 0, symtab.clone()) },
+                       group,
+                       TestSection::new(".text.f", 1, SHF_ALLOC | SHF_GROUP, vec![0x90])])
+        };
+
+        let elf = mk_elf(mk_group(sections::GRP_COMDAT as u32));
+        let elf = ElfFile::new(&elf).unwrap();
+        let group = elf.find_section_by_name(".group").unwrap();
+        assert!(group.get_data(&elf).unwrap().is_comdat());
+        assert_eq!(group.group_signature(&elf), Ok("f"));
+
+        let elf = mk_elf(TestSection { info: 2, ..mk_group(0) });
+        let elf = ElfFile::new(&elf).unwrap();
+        let group = elf.find_section_by_name(".group").unwrap();
+        assert!(!group.get_data(&elf).unwrap().is_comdat());
+        assert_eq!(group.group_signature(&elf), Err("Group signature symbol out of range"));
+        let text = elf.find_section_by_name(".text.f").unwrap();
+        assert!(!text.get_data(&elf).unwrap().is_comdat());
+        assert_eq!(text.group_signature(&elf), Err("Not a group section"));
+    }
+
+    #[test]
+    fn extended_symbol_section_index() {
+        // The null symbol, then a symbol in section SHN_XINDEX.
+        let mut symtab = vec![0; 24];
+        symtab.extend_from_slice(&[1, 0, 0, 0, 0x12, 0, 0xff, 0xff]);
+        symtab.extend_from_slice(&[0; 16]);
+        let shndx = le_bytes(&[0, 1]);
+
+        let symtab = TestSection { link: 2, ..TestSection::new(".symtab", 2, 0, symtab) };
+        // A SymTabShIndex section for some other symbol table comes first.
+        let other = TestSection { link: 1, ..TestSection::new(".other", 18, 0, le_bytes(&[0, 7])) };
+        let shndx = TestSection { link: 3, ..TestSection::new(".symtab_shndx", 18, 0, shndx) };
+        let elf = mk_elf64(&[TestSection::new(".text", 1, sections::SHF_ALLOC, vec![0x90]),
+                             TestSection::new(".strtab", 3, 0, b"\0f\0".to_vec()),
+                             symtab,
+                             other,
+                             shndx]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let symtab = elf.find_section_by_name(".symtab").unwrap();
+        match symtab.get_data(&elf) {
+            Ok(sections::SectionData::SymbolTable64(entries)) => {
+                assert_eq!(entries[0].get_section_index(&elf, symtab), Some(0));
+                assert_eq!(entries[1].shndx(), sections::SHN_XINDEX);
+                assert_eq!(entries[1].get_section_index(&elf, symtab), Some(1));
+            }
+            other => panic!("unexpected section data {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "build-id")]
+    fn build_id_sha1() {
+        // A minimal, stripped i386 executable linked with `ld --build-id=sha1`.
+        let elf = include_bytes!("../test-data/build-id-sha1").to_vec();
+        let elf = ElfFile::new(&elf).unwrap();
+        let build_id = compute_build_id_sha1(&elf).unwrap();
+        assert_eq!(elf.get_gnu_buildid(), Some(&build_id[..]));
+
+        // `e_phoff` near the top of the address space.
+        let mut elf = mk_elf64(&[]);
+        put(&mut elf, 32, &(u64::MAX - 8).to_le_bytes());
+        let elf = ElfFile::new(&elf).unwrap();
+        assert_eq!(compute_build_id_sha1(&elf), Err("Program header out of range"));
+    }
+
+    #[test]
+    fn package_metadata() {
+        // A minimal x86-64 executable linked with `ld --package-metadata`.
+        let elf = include_bytes!("../test-data/package-metadata").to_vec();
+        let elf = ElfFile::new(&elf).unwrap();
+        let json = r#"{"type":"deb","os":"debian","name":"hello","version":"1.0"}"#;
+        assert_eq!(elf.package_metadata(), Some(json));
+        assert_eq!(elf.get_package_metadata(), Some(json));
+    }
+
+    #[test]
+    fn security_report() {
+        use security::{Relro, SecurityReport};
+
+        // A small x86-64 program using strcpy and printf, built with `-O2 -fPIE
+        // -pie -fstack-protector-strong -D_FORTIFY_SOURCE=2 -fcf-protection=full
+        // -Wl,-z,relro,-z,now,-z,noexecstack,-z,ibt,-z,shstk -s`.
+        let elf = include_bytes!("../test-data/hardened").to_vec();
+        let elf = ElfFile::new(&elf).unwrap();
+        assert_eq!(elf.security_report(),
+                   SecurityReport {
+                       pie: true,
+                       relro: Relro::Full,
+                       bind_now: true,
+                       nx_stack: true,
+                       fortify: true,
+                       stack_protector: true,
+                       cf_protection: true,
+                       rpath: false,
+                       runpath: false,
+                   });
+
+        let elf = mk_elf64(&[]);
+        assert_eq!(ElfFile::new(&elf).unwrap().security_report(),
+                   SecurityReport {
+                       pie: false,
+                       relro: Relro::None,
+                       bind_now: false,
+                       nx_stack: false,
+                       fortify: false,
+                       stack_protector: false,
+                       cf_protection: false,
+                       rpath: false,
+                       runpath: false,
+                   });
+    }
+
+    #[test]
+    fn soname() {
+        // DT_SONAME
+        let mk_elf = |soname: u64| mk_dynamic_elf(b"\0libc.so.6\0", &[14, soname]);
+
+        assert_eq!(ElfFile::new(&mk_elf(1)).unwrap().soname(), Some("libc.so.6"));
+        assert_eq!(ElfFile::new(&mk_elf(8)).unwrap().soname(), Some(".6"));
+        assert_eq!(ElfFile::new(&mk_elf(11)).unwrap().soname(), None);
+        assert_eq!(ElfFile::new(&mk_elf64(&[])).unwrap().soname(), None);
+    }
+
+    #[test]
+    fn is_pie() {
+        let set_type = |mut elf: Vec<u8>, type_: u16| {
+            elf[16..18].copy_from_slice(&type_.to_le_bytes());
+            elf
+        };
+        // DT_FLAGS_1
+        let pie = mk_dynamic_elf(b"\0", &[0x6ffffffb, dynamic::FLAG_1_PIE | dynamic::FLAG_1_NOW]);
+        assert!(ElfFile::new(&set_type(pie.clone(), 3)).unwrap().is_pie());
+        assert!(!ElfFile::new(&pie).unwrap().is_pie());
+
+        let library = mk_dynamic_elf(b"\0", &[0x6ffffffb, dynamic::FLAG_1_NOW]);
+        assert!(!ElfFile::new(&set_type(library, 3)).unwrap().is_pie());
+        assert!(!ElfFile::new(&set_type(mk_elf64(&[]), 3)).unwrap().is_pie());
+    }
+
+    #[test]
+    fn rpath_and_runpath() {
+        // DT_RUNPATH
+        let elf = mk_dynamic_elf(b"\0$ORIGIN/../lib:/opt/lib\0", &[29, 1]);
+        let elf = ElfFile::new(&elf).unwrap();
+        assert_eq!(elf.runpath(), Some("$ORIGIN/../lib:/opt/lib"));
+        assert_eq!(elf.rpath(), None);
+
+        // DT_RPATH
+        let elf = mk_dynamic_elf(b"\0/usr/local/lib\0", &[15, 1]);
+        let elf = ElfFile::new(&elf).unwrap();
+        assert_eq!(elf.rpath(), Some("/usr/local/lib"));
+        assert_eq!(elf.runpath(), None);
+    }
+
+    #[test]
+    fn dynamic_entries() {
+        use dynamic::Tag;
+
+        // DT_NEEDED, an invalid tag, DT_FLAGS, DT_GNU_HASH, DT_NULL, DT_SONAME.
+        let mut dynamic = vec![];
+        for &word in &[1, 1, 0x5000_0000, 0, 30, 8, dynamic::TAG_GNU_HASH, 0x100, 0, 0, 14, 1u64] {
+            dynamic.extend_from_slice(&word.to_le_bytes());
+        }
+        let elf = mk_elf64(&[TestSection::new(".dynamic", 6, sections::SHF_ALLOC, dynamic)]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let entries: Vec<_> = elf.dynamic_entries().collect();
+        assert_eq!(entries,
+                   [(Tag::Needed, 1),
+                    (Tag::Flags, dynamic::FLAG_BIND_NOW),
+                    (Tag::OsSpecific(dynamic::TAG_GNU_HASH), 0x100)]);
+        assert_eq!(elf.get_dynamic_value(Tag::SoName), None);
+    }
+
+    #[test]
+    fn dynamic_section_width() {
+        use dynamic::Tag;
+
+        // DT_NEEDED, DT_STRSZ, DT_NULL, as 64-bit words.
+        let mut dynamic = vec![];
+        for &word in &[1, 7, 10, 0x1234, 0, 0u64] {
+            dynamic.extend_from_slice(&word.to_le_bytes());
+        }
+        let elf = mk_elf64(&[TestSection::new(".dynamic", 6, sections::SHF_ALLOC, dynamic)]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        match elf.find_section_by_name(".dynamic").unwrap().get_data(&elf) {
+            Ok(sections::SectionData::Dynamic64(entries)) => {
+                assert_eq!(entries.len(), 3);
+                assert_eq!(entries[1].get_tag(), Ok(Tag::StrSize));
+                assert_eq!(entries[1].get_val(), Ok(0x1234));
+            }
+            other => panic!("unexpected section data {:?}", other),
+        }
+    }
+
+    #[test]
+    fn dynamic_strtab() {
+        let mk_elf = |strtab_size: u64| {
+            let mut elf = mk_dynamic_elf(b"\0libc.so.6\0", &[]);
+            // The value of DT_STRSZ.
+            let dynamic = ElfFile::new(&elf).unwrap().find_section_by_name(".dynamic").unwrap();
+            let offset = dynamic.offset() as usize + 24;
+            put(&mut elf, offset, &strtab_size.to_le_bytes());
+            elf
+        };
+
+        assert_eq!(ElfFile::new(&mk_elf(11)).unwrap().validate_dynamic_strtab(), Ok(()));
+        assert_eq!(ElfFile::new(&mk_elf(10)).unwrap().validate_dynamic_strtab(),
+                   Err("Dynamic string table does not start and end with a null byte"));
+        assert_eq!(ElfFile::new(&mk_elf(0x1000)).unwrap().validate_dynamic_strtab(),
+                   Err("Dynamic string table out of range"));
+    }
+
+    #[test]
+    fn loadable_segments() {
+        let mut elf = mk_elf64(&[]);
+        assert!(ElfFile::new(&elf).unwrap().program_header(0).unwrap().is_loadable());
+        // PT_NOTE
+        elf[64] = 4;
+        let file = ElfFile::new(&elf).unwrap();
+        assert_eq!(file.program_header(0).unwrap().get_type(), Ok(program::Type::Note));
+        assert!(!file.program_header(0).unwrap().is_loadable());
+    }
+
+    #[test]
+    fn load_segment() {
+        let mut elf = mk_elf64(&[]);
+        let len = elf.len() as u64;
+        // Give the segment some zero-filled memory after the file contents.
+        elf[64 + 40..64 + 48].copy_from_slice(&(len + 0x100).to_le_bytes());
+        {
+            let file = ElfFile::new(&elf).unwrap();
+            let segments: Vec<_> = file.loadable_segments().collect();
+            assert_eq!(segments.len(), 1);
+            let segment = segments[0];
+            assert_eq!((segment.virtual_addr(), segment.file_offset()), (0, 0));
+            assert_eq!((segment.file_size(), segment.mem_size()), (len, len + 0x100));
+            assert!(segment.flags().is_execute());
+            assert_eq!(segment.data().map(|data| data.as_ptr()), Ok(elf.as_ptr()));
+        }
+
+        elf[64 + 8..64 + 16].copy_from_slice(&1u64.to_le_bytes());
+        {
+            let file = ElfFile::new(&elf).unwrap();
+            assert_eq!(file.loadable_segments().next().unwrap().data(),
+                       Err("Segment data out of range"));
+        }
+
+        elf[64 + 8..64 + 16].copy_from_slice(&0u64.to_le_bytes());
+        elf[64 + 40..64 + 48].copy_from_slice(&(len - 1).to_le_bytes());
+        let file = ElfFile::new(&elf).unwrap();
+        assert_eq!(file.loadable_segments().next().unwrap().data(),
+                   Err("Segment file size exceeds its memory size"));
+    }
+
+    #[test]
+    fn program_raw_data() {
+        let mut elf = mk_elf64(&[]);
+        let len = elf.len() as u64;
+        elf[64 + 40..64 + 48].copy_from_slice(&(len + 0x100).to_le_bytes());
+        {
+            let file = ElfFile::new(&elf).unwrap();
+            let segment = file.program_header(0).unwrap();
+            assert_eq!(segment.raw_data(&file).len() as u64, len);
+            assert_eq!(segment.try_raw_data(&file).map(|data| data.len() as u64), Ok(len));
+        }
+
+        elf[64 + 32..64 + 40].copy_from_slice(&(len + 1).to_le_bytes());
+        let file = ElfFile::new(&elf).unwrap();
+        let segment = file.program_header(0).unwrap();
+        assert_eq!(segment.try_raw_data(&file), Err("Segment data out of range"));
+    }
+
+    #[test]
+    fn tls_template() {
+        let mut elf = mk_elf64(&[]);
+        assert_eq!(ElfFile::new(&elf).unwrap().tls_template(), None);
+
+        elf[64..68].copy_from_slice(&7u32.to_le_bytes());
+        elf[64 + 32..64 + 40].copy_from_slice(&0x10u64.to_le_bytes());
+        elf[64 + 40..64 + 48].copy_from_slice(&0x30u64.to_le_bytes());
+        elf[64 + 48..64 + 56].copy_from_slice(&0x10u64.to_le_bytes());
+        let file = ElfFile::new(&elf).unwrap();
+        assert_eq!(file.tls_template(),
+                   Some(TlsInfo {
+                       file_offset: 0,
+                       file_size: 0x10,
+                       mem_size: 0x30,
+                       align: 0x10,
+                   }));
+    }
+
+    #[test]
+    fn interpreter() {
+        let mut elf = mk_elf64(&[TestSection::new(".interp",
+                                                  1,
+                                                  0,
+                                                  b"/lib/ld.so\0trailing".to_vec())]);
+        let offset = ElfFile::new(&elf).unwrap().find_section_by_name(".interp").unwrap().offset();
+        assert_eq!(ElfFile::new(&elf).unwrap().interpreter(), None);
+
+        // Turn the Load segment into a PT_INTERP covering exactly the path.
+        elf[64] = 3;
+        elf[64 + 8..64 + 16].copy_from_slice(&offset.to_le_bytes());
+        elf[64 + 32..64 + 40].copy_from_slice(&11u64.to_le_bytes());
+        assert_eq!(ElfFile::new(&elf).unwrap().interpreter(), Some("/lib/ld.so"));
+
+        // Without the null byte, the path must not run on into the next bytes.
+        elf[64 + 32..64 + 40].copy_from_slice(&10u64.to_le_bytes());
+        assert_eq!(ElfFile::new(&elf).unwrap().interpreter(), None);
+    }
+
+    #[test]
+    fn segment_flags() {
+        let mut elf = mk_elf64(&[]);
+        // PF_R | PF_X
+        elf[64 + 4] = 5;
+        let file = ElfFile::new(&elf).unwrap();
+        let flags = file.program_header(0).unwrap().get_flags();
+        assert!(flags.is_read() && !flags.is_write() && flags.is_execute());
+        assert_eq!(format!("{}", flags), "R-X");
+        assert_eq!(format!("{:?}", flags), "SegmentFlags(PF_R | PF_X)");
+        assert_eq!(format!("{:?}", program::SegmentFlags(0x100002)),
+                   "SegmentFlags(PF_W | 0x100000)");
+        assert_eq!(format!("{}", program::SegmentFlags(0)), "---");
+    }
+
+    #[test]
+    fn segment_data_out_of_range() {
+        let mut elf = mk_elf64(&[]);
+        let len = elf.len();
+        {
+            let file = ElfFile::new(&elf).unwrap();
+            match file.program_header(0).unwrap().get_data(&file) {
+                Ok(program::SegmentData::Undefined(data)) => assert_eq!(data.len(), len),
+                other => panic!("unexpected segment data {:?}", other),
+            }
+        }
+
+        // Grow the Load segment's file size past the end of the file.
+        elf[64 + 32..64 + 40].copy_from_slice(&(len as u64 + 1).to_le_bytes());
+        let file = ElfFile::new(&elf).unwrap();
+        assert_eq!(file.program_header(0).unwrap().get_data(&file).err(),
+                   Some("Segment data out of range"));
+    }
+
+    #[test]
+    fn symbol_count() {
+        let elf = mk_elf64(&[TestSection::new(".strtab", 3, 0, vec![0]),
+                             TestSection::new(".symtab", 2, 0, vec![0; 24 * 3])]);
+        let elf = ElfFile::new(&elf).unwrap();
+        match elf.find_section_by_name(".symtab").unwrap().get_data(&elf) {
+            Ok(sections::SectionData::SymbolTable64(entries)) => {
+                assert_eq!(elf.symbol_count(), entries.iter().count());
+                assert_eq!(elf.symbol_count(), 3);
+            }
+            other => panic!("unexpected section data {:?}", other),
+        }
+
+        let dynsym = TestSection::new(".dynsym", 11, sections::SHF_ALLOC, vec![0; 24 * 2]);
+        let elf = mk_elf64(&[dynsym]);
+        assert_eq!(ElfFile::new(&elf).unwrap().symbol_count(), 2);
+        assert_eq!(ElfFile::new(&mk_elf64(&[])).unwrap().symbol_count(), 0);
+    }
+
+    #[test]
+    fn hash_table_symbol_count() {
+        // Without a symbol table, the count comes from the hash table at 120,
+        // the first section's address.
+        let mk_elf = |hash_type: u32, hash_tag: u64, table: Vec<u8>| {
+            let mut dynamic = vec![];
+            for &word in &[hash_tag, 120, 0, 0] {
+                dynamic.extend_from_slice(&word.to_le_bytes());
+            }
+            mk_elf64(&[TestSection::new(".hash", hash_type, sections::SHF_ALLOC, table),
+                       TestSection::new(".dynamic", 6, sections::SHF_ALLOC, dynamic)])
+        };
+
+        // DT_HASH, with one bucket and five chains (one per symbol).
+        let elf = mk_elf(5, 4, le_bytes(&[1, 5, 1, 0, 0, 2, 3, 4]));
+        assert_eq!(ElfFile::new(&elf).unwrap().symbol_count(), 5);
+
+        // DT_GNU_HASH, with symbols 1 to 3 in the only bucket, ending the chain
+        // at symbol 3.
+        let gnu_hash = le_bytes(&[1, 1, 1, 6, 0, 0, 1, 0, 0, 1]);
+        let elf = mk_elf(0x6ffffff6, 0x6ffffef5, gnu_hash);
+        assert_eq!(ElfFile::new(&elf).unwrap().symbol_count(), 4);
+
+        // A DT_HASH table which is not in any Load segment.
+        let mut elf = mk_elf(5, 4, le_bytes(&[1, 5, 1, 0, 0, 2, 3, 4]));
+        put(&mut elf, 64 + 32, &100u64.to_le_bytes());
+        assert_eq!(ElfFile::new(&elf).unwrap().symbol_count(), 0);
+    }
+
+    #[test]
+    fn gcc_except_table() {
+        let lsdas = vec![0xff, 0xff, 0x01, 0x04, 0xff, 0x9b, 0x01, 0x00];
+        let table = TestSection::new(".gcc_except_table", 1, sections::SHF_ALLOC, lsdas.clone());
+        let elf = mk_elf64(&[table]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let header = elf.find_section_by_name(".gcc_except_table").unwrap();
+        match elf.gcc_except_table() {
+            Some(sections::SectionData::GccExceptTable(data)) => assert_eq!(data, &lsdas[..]),
+            other => panic!("unexpected section data {:?}", other),
+        }
+        match header.get_data(&elf) {
+            Ok(sections::SectionData::Undefined(data)) => assert_eq!(data, &lsdas[..]),
+            other => panic!("unexpected section data {:?}", other),
+        }
+
+        assert_eq!(elf.get_lsda(header.address()), Some(&lsdas[..]));
+        assert_eq!(elf.get_lsda(header.address() + 4), Some(&lsdas[4..]));
+        assert_eq!(elf.get_lsda(header.address() - 1), None);
+    }
+
+    #[test]
+    fn symbol_for_address() {
+        let mut symtab = vec![0; 24];
+        // STT_FUNC "main", STT_OBJECT "table" with no size, and undefined "puts".
+        symtab.extend(mk_symbol64(1, 2, 1, 0x1000, 0x10));
+        symtab.extend(mk_symbol64(6, 1, 1, 0x2000, 0));
+        symtab.extend(mk_symbol64(12, 2, 0, 0, 0));
+        let elf = mk_elf64(&[TestSection::new(".strtab", 3, 0, b"\0main\0table\0puts\0".to_vec()),
+                             TestSection::new(".symtab", 2, 0, symtab)]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let name = |addr| elf.symbol_for_address(addr).map(|(_, name)| name);
+        assert_eq!(name(0x1000), Some("main"));
+        assert_eq!(name(0x100f), Some("main"));
+        assert_eq!(name(0x1010), None);
+        assert_eq!(name(0x2000), Some("table"));
+        assert_eq!(name(0x2345), Some("table"));
+        assert_eq!(name(0), None);
+        assert_eq!(elf.symbol_for_address(0x1008).unwrap().0.size(), 0x10);
+    }
+
+    #[test]
+    #[cfg(feature = "compression")]
+    fn section_map() {
+        use flate2::{Compress, Compression, FlushCompress};
+
+        let debug_info = vec![0xab; 100];
+        // An Elf64_Chdr for zlib, then the compressed data.
+        let mut compressed = vec![];
+        for &word in &[1u32, 0] {
+            compressed.extend_from_slice(&word.to_le_bytes());
+        }
+        for &word in &[debug_info.len() as u64, 1] {
+            compressed.extend_from_slice(&word.to_le_bytes());
+        }
+        let mut zlib = Vec::with_capacity(200);
+        let _ = Compress::new(Compression::default(), true)
+            .compress_vec(&debug_info, &mut zlib, FlushCompress::Finish)
+            .unwrap();
+        compressed.extend_from_slice(&zlib);
+
+        let mut elf = mk_elf64(&[TestSection::new(".text", 1, sections::SHF_ALLOC, vec![0x90; 3]),
+                                 TestSection::new(".debug_info",
+                                                  1,
+                                                  sections::SHF_COMPRESSED,
+                                                  compressed)]);
+        {
+            let file = ElfFile::new(&elf).unwrap();
+            let map = file.section_map().unwrap();
+            assert_eq!(map[".text"].len(), 3);
+            assert_eq!(map[".debug_info"], &debug_info[..]);
+        }
+
+        // A .text size which runs past the end of the file.
+        let sh_offset = ElfFile::new(&elf).unwrap().header.section_header_offset() as usize;
+        put(&mut elf, sh_offset + 64 + 32, &0x10000u64.to_le_bytes());
+        assert!(ElfFile::new(&elf).unwrap().section_map().is_err());
+    }
+
+    #[test]
+    fn is_symtab_address_sorted() {
+        let elf_with = |values: &[u64]| {
+            let mut symtab = vec![0; 24];
+            for &value in values {
+                symtab.extend(mk_symbol64(0, 2, 1, value, 0x10));
+            }
+            // An object symbol out of order, which is ignored.
+            symtab.extend(mk_symbol64(0, 1, 1, 0, 0x10));
+            mk_elf64(&[TestSection::new(".strtab", 3, 0, vec![0]),
+                       TestSection { link: 1, ..TestSection::new(".symtab", 2, 0, symtab) }])
+        };
+        let sorted = elf_with(&[0x1000, 0x1000, 0x2000]);
+        assert!(ElfFile::new(&sorted).unwrap().is_symtab_address_sorted());
+        let unsorted = elf_with(&[0x1000, 0x2000, 0x1800]);
+        assert!(!ElfFile::new(&unsorted).unwrap().is_symtab_address_sorted());
+        let no_symtab = mk_elf64(&[]);
+        assert!(!ElfFile::new(&no_symtab).unwrap().is_symtab_address_sorted());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn symbol_address_index() {
+        let mut symtab = vec![0; 24];
+        // STT_FUNC "main" and "start" out of order, "empty" with no size, and
+        // undefined "puts".
+        symtab.extend(mk_symbol64(1, 2, 1, 0x1000, 0x10));
+        symtab.extend(mk_symbol64(6, 2, 1, 0x800, 0x20));
+        symtab.extend(mk_symbol64(12, 2, 1, 0x3000, 0));
+        symtab.extend(mk_symbol64(18, 2, 0, 0, 0));
+        let strtab = b"\0main\0start\0empty\0puts\0".to_vec();
+        let elf = mk_elf64(&[TestSection::new(".strtab", 3, 0, strtab),
+                             TestSection::new(".symtab", 2, 0, symtab)]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let index = symbol_table::SymbolAddressIndex::new(&elf);
+        let name = |addr| index.symbol_for_address(&elf, addr).map(|(_, name)| name);
+        assert_eq!(name(0), None);
+        assert_eq!(name(0x81f), Some("start"));
+        assert_eq!(name(0x820), None);
+        assert_eq!(name(0x1000), Some("main"));
+        assert_eq!(name(0x100f), Some("main"));
+        assert_eq!(name(0x3456), Some("empty"));
+    }
+
+    #[test]
+    fn resolve_symbol() {
+        // "puts" is undefined in .symtab but defined in .dynsym; "exit" is only
+        // ever undefined.
+        let mut symtab = vec![0; 24];
+        symtab.extend(mk_symbol64(1, 2, 0, 0, 0));
+        symtab.extend(mk_symbol64(6, 2, 0, 0, 0));
+        let mut dynsym = vec![0; 24];
+        dynsym.extend(mk_symbol64(1, 2, 1, 0x1000, 0x10));
+        let elf = mk_elf64(&[TestSection::new(".strtab", 3, 0, b"\0puts\0exit\0".to_vec()),
+                             TestSection { link: 1, ..TestSection::new(".symtab", 2, 0, symtab) },
+                             TestSection::new(".dynstr", 3, 0, b"\0puts\0".to_vec()),
+                             TestSection {
+                                 link: 3,
+                                 ..TestSection::new(".dynsym", 11, 0, dynsym)
+                             }]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let puts = elf.resolve_symbol("puts").unwrap();
+        assert_eq!((puts.shndx(), puts.value()), (1, 0x1000));
+        assert_eq!(elf.resolve_symbol("exit").unwrap().shndx(), sections::SHN_UNDEF);
+        assert!(elf.resolve_symbol("main").is_none());
+    }
+
+    #[test]
+    fn lookup_symbol() {
+        // An undefined "main" before the defined one, and "puts" only in .dynsym.
+        let mut symtab = vec![0; 24];
+        symtab.extend(mk_symbol64(1, 2, 0, 0, 0));
+        symtab.extend(mk_symbol64(1, 2, 1, 0x1000, 0x10));
+        let mut dynsym = vec![0; 24];
+        dynsym.extend(mk_symbol64(1, 2, 1, 0x2000, 0x10));
+        let dynsym = |link| {
+            TestSection { link: link, ..TestSection::new(".dynsym", 11, 0, dynsym.clone()) }
+        };
+        let elf = mk_elf64(&[TestSection::new(".strtab", 3, 0, b"\0main\0".to_vec()),
+                             TestSection { link: 1, ..TestSection::new(".symtab", 2, 0, symtab) },
+                             TestSection::new(".dynstr", 3, 0, b"\0puts\0".to_vec()),
+                             dynsym(3)]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let (main, name) = elf.lookup_symbol("main").unwrap();
+        assert_eq!((main.value(), name), (0x1000, "main"));
+        assert!(elf.lookup_symbol("puts").is_none());
+
+        let stripped = mk_elf64(&[TestSection::new(".dynstr", 3, 0, b"\0puts\0".to_vec()),
+                                  dynsym(1)]);
+        let stripped = ElfFile::new(&stripped).unwrap();
+        let (puts, name) = stripped.lookup_symbol("puts").unwrap();
+        assert_eq!((puts.value(), name), (0x2000, "puts"));
+    }
+
+    #[test]
+    fn imported_and_exported_symbols() {
+        // Undefined "puts", global "main", local "helper" and weak "data".
+        let mut dynsym = vec![0; 24];
+        dynsym.extend(mk_symbol64(1, 0x12, 0, 0, 0));
+        dynsym.extend(mk_symbol64(6, 0x12, 1, 0x1000, 0x10));
+        dynsym.extend(mk_symbol64(11, 0x02, 1, 0x1010, 0x10));
+        dynsym.extend(mk_symbol64(18, 0x21, 1, 0x2000, 0x8));
+        let dynstr = b"\0puts\0main\0helper\0data\0".to_vec();
+        let dynsym = TestSection { link: 1, ..TestSection::new(".dynsym", 11, 0, dynsym) };
+        let elf = mk_elf64(&[TestSection::new(".dynstr", 3, 0, dynstr), dynsym]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let imports: Vec<_> = elf.imported_symbols().map(|e| e.get_name(&elf).unwrap()).collect();
+        assert_eq!(imports, ["puts"]);
+        let exports: Vec<_> = elf.exported_symbols().map(|e| e.get_name(&elf).unwrap()).collect();
+        assert_eq!(exports, ["main", "data"]);
+        assert!(elf.imported_symbols().all(|entry| !entry.is_defined()));
+        assert_eq!(ElfFile::new(&mk_elf64(&[])).unwrap().exported_symbols().count(), 0);
+    }
+
+    #[test]
+    fn symbol_section() {
+        use symbol_table::{Entry64, SymbolSection};
+
+        let section = |shndx| {
+            let entry = mk_symbol64(0, 0, shndx, 0, 0);
+            let entry: &Entry64 = read(&entry);
+            entry.section()
+        };
+        assert_eq!(section(sections::SHN_UNDEF), SymbolSection::Undefined);
+        assert_eq!(section(5), SymbolSection::Defined(5));
+        assert_eq!(section(sections::SHN_ABS), SymbolSection::Absolute);
+        assert_eq!(section(sections::SHN_COMMON), SymbolSection::Common);
+        assert_eq!(section(sections::SHN_XINDEX), SymbolSection::Extended);
+        assert_eq!(section(0xff02), SymbolSection::Reserved(0xff02));
+    }
+
+    #[test]
+    fn is_stripped() {
+        let elf = mk_elf64(&[TestSection::new(".dynsym", 11, 0, vec![0; 24])]);
+        let elf = ElfFile::new(&elf).unwrap();
+        assert!(elf.is_stripped());
+        assert!(!elf.has_debug_info());
+
+        let elf = mk_elf64(&[TestSection::new(".symtab", 2, 0, vec![0; 24]),
+                             TestSection::new(".debug_info", 1, 0, vec![0; 4])]);
+        let elf = ElfFile::new(&elf).unwrap();
+        assert!(!elf.is_stripped());
+        assert!(elf.has_debug_info());
+
+        let elf = mk_elf64(&[TestSection::new(".zdebug_line", 1, 0, vec![0; 4])]);
+        assert!(ElfFile::new(&elf).unwrap().has_debug_info());
+    }
+
+    #[test]
+    fn debug_sections() {
+        let elf = mk_elf64(&[TestSection::new(".debug_info", 1, 0, vec![0; 4]),
+                             TestSection::new(".text", 1, 0, vec![0; 4]),
+                             TestSection::new(".zdebug_line", 1, 0, vec![0; 4]),
+                             TestSection::new(".debug_foo", 1, 0, vec![0; 4])]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let sections: Vec<_> = elf.debug_sections()
+                                  .map(|(dwarf, sect)| (dwarf, sect.get_name(&elf).unwrap()))
+                                  .collect();
+        assert_eq!(sections,
+                   [(DwarfSection::Info, ".debug_info"), (DwarfSection::Line, ".zdebug_line")]);
+        assert_eq!(DwarfSection::StrOffsets.name(), ".debug_str_offsets");
+        assert_eq!(DwarfSection::from_name(".debug_str_offsets"), Some(DwarfSection::StrOffsets));
+    }
+
+    #[test]
+    fn symbol_slice() {
+        let mut symtab = vec![0; 24];
+        symtab.extend(mk_symbol64(1, 2, 1, 0x1000, 0x10));
+        symtab.extend(mk_symbol64(6, 1, 1, 0x2000, 8));
+        let elf = mk_elf64(&[TestSection::new(".strtab", 3, 0, b"\0main\0table\0".to_vec()),
+                             TestSection::new(".symtab", 2, 0, symtab)]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let data = elf.find_section_by_name(".symtab").unwrap().get_data(&elf).unwrap();
+        let symbols = data.symbols().unwrap();
+        assert_eq!(symbols.len(), 3);
+        let table = symbols.get(2).unwrap();
+        assert_eq!((table.value(), table.size(), table.name()), (0x2000, 8, 6));
+        assert!(symbols.get(3).is_none());
+        let names: Vec<_> = symbols.iter().map(|e| e.get_name(&elf).unwrap()).collect();
+        assert_eq!(names, ["", "main", "table"]);
+
+        let strtab = elf.find_section_by_name(".strtab").unwrap().get_data(&elf).unwrap();
+        assert!(strtab.symbols().is_none());
+    }
+
+    #[test]
+    fn offset_from_entry() {
+        let mut symtab = vec![0; 24];
+        // STT_FUNC "_start" and "main", STT_OBJECT "table", and undefined "puts".
+        symtab.extend(mk_symbol64(1, 2, 1, 0x1040, 0x20));
+        symtab.extend(mk_symbol64(8, 2, 1, 0x1000, 0x10));
+        symtab.extend(mk_symbol64(13, 1, 1, 0x2000, 8));
+        symtab.extend(mk_symbol64(19, 2, 0, 0, 0));
+        let mut elf = mk_elf64(&[TestSection::new(".strtab",
+                                                  3,
+                                                  0,
+                                                  b"\0_start\0main\0table\0puts\0".to_vec()),
+                                 TestSection::new(".symtab", 2, 0, symtab)]);
+        elf[24..32].copy_from_slice(&0x1040u64.to_le_bytes());
+        let elf = ElfFile::new(&elf).unwrap();
+
+        match elf.find_section_by_name(".symtab").unwrap().get_data(&elf) {
+            Ok(sections::SectionData::SymbolTable64(entries)) => {
+                let offsets: Vec<_> = entries[1..].iter()
+                                                  .map(|e| e.offset_from_entry(&elf))
+                                                  .collect();
+                assert_eq!(offsets, [Some(0), Some(-0x40), None, None]);
+            }
+            other => panic!("unexpected section data {:?}", other),
+        }
+    }
+
+    #[test]
+    fn truncated_symbol_table() {
+        let mut symtab = vec![0; 24];
+        symtab.extend(mk_symbol64(1, 2, 1, 0x1000, 0x10));
+        symtab.extend(&[0; 5]);
+        let elf = mk_elf64(&[TestSection::new(".strtab", 3, 0, b"\0main\0".to_vec()),
+                             TestSection::new(".symtab", 2, 0, symtab)]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let symtab = elf.find_section_by_name(".symtab").unwrap();
+        assert_eq!(symtab.get_data(&elf).err(), Some("Data is not a whole number of entries"));
+        assert_eq!(try_read_array::<u32>(&[0; 6]), Err("Data is not a whole number of entries"));
+        let words = [0u32; 2];
+        let bytes = unsafe { slice::from_raw_parts(words.as_ptr() as *const u8, 8) };
+        assert_eq!(try_read_array::<u32>(&bytes[1..5]), Err("Data is misaligned"));
+        assert_eq!(try_read_array::<u32>(&bytes[4..8]), Ok(&[0u32][..]));
+    }
+
+    #[test]
+    fn section_iter_malformed() {
+        let elf = mk_elf64(&[TestSection::new(".text", 1, 0, vec![0; 4])]);
+        let with_header = |offset: usize, bytes: &[u8]| {
+            let mut elf = elf.clone();
+            elf[offset..offset + bytes.len()].copy_from_slice(bytes);
+            elf
+        };
+        let count = |elf: &[u8]| ElfFile::new(elf).unwrap().section_iter().count();
+        assert_eq!(count(&elf), 3);
+
+        // More sections than the file holds, up to the reserved indices.
+        assert_eq!(count(&with_header(60, &[100, 0])), 3);
+        assert_eq!(count(&with_header(60, &[0xff, 0xff])), 3);
+        // Entries too small to hold a section header, and a misaligned table.
+        assert_eq!(count(&with_header(58, &[8, 0])), 0);
+        let sh_offset = ElfFile::new(&elf).unwrap().header.section_header_offset() + 4;
+        assert_eq!(count(&with_header(40, &sh_offset.to_le_bytes())), 0);
+    }
+
+    #[test]
+    fn get_data_truncated() {
+        let hash = le_bytes(&[4, 4, 0, 0]);
+        let mut elf = mk_elf64(&[TestSection::new(".note", 7, 0, vec![0; 8]),
+                                 TestSection::new(".hash", 5, 0, hash),
+                                 TestSection::new(".group", 17, 0, vec![0; 2]),
+                                 TestSection::new(".symtab", 2, 0, vec![0; 24])]);
+        // Make .symtab (section 4) extend past the end of the file.
+        let symtab = ElfFile::new(&elf).unwrap().header.section_header_offset() as usize + 4 * 64;
+        elf[symtab + 32..symtab + 40].copy_from_slice(&0x100000u64.to_le_bytes());
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let data = |name| elf.find_section_by_name(name).unwrap().get_data(&elf).err();
+        assert_eq!(data(".note"), Some("Note section too short"));
+        assert_eq!(data(".hash"), Some("Hash section too short"));
+        assert_eq!(data(".group"), Some("Group section too short"));
+        assert_eq!(data(".symtab"), Some("Section data out of range"));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn build_symbol_index() {
+        let mut symtab = vec![0; 24];
+        symtab.extend(mk_symbol64(1, 2, 1, 0x1000, 0x10));
+        symtab.extend(mk_symbol64(6, 1, 1, 0x2000, 8));
+        let elf = mk_elf64(&[TestSection::new(".strtab", 3, 0, b"\0main\0table\0".to_vec()),
+                             TestSection::new(".symtab", 2, 0, symtab)]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let index = elf.build_symbol_index();
+        assert_eq!(index.len(), 2);
+        match elf.find_section_by_name(".symtab").unwrap().get_data(&elf) {
+            Ok(sections::SectionData::SymbolTable64(entries)) => {
+                assert_eq!(entries[index["table"]].value(), 0x2000);
+                assert_eq!(entries[index["main"]].get_name(&elf), Ok("main"));
+            }
+            other => panic!("unexpected section data {:?}", other),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn elf_reader() {
+        use std::io::Cursor;
+        use sections::{SHF_ALLOC, SHF_WRITE};
+
+        let input = mk_elf64(&[TestSection::new(".text", 1, SHF_ALLOC, vec![0x90; 8]),
+                               TestSection::new(".bss", 8, SHF_ALLOC | SHF_WRITE, vec![0; 8])]);
+        let elf = ElfFile::new(&input).unwrap();
+        let reader = reader::ElfReader::new(Cursor::new(input.clone())).unwrap();
+
+        assert_eq!(reader.header().pt2.entry_point(), elf.header.pt2.entry_point());
+        let names: Vec<_> = reader.section_iter()
+                                  .skip(1)
+                                  .map(|s| reader.section_name(s).unwrap().to_owned())
+                                  .collect();
+        let expected: Vec<_> = elf.section_iter()
+                                  .skip(1)
+                                  .map(|s| s.get_name(&elf).unwrap())
+                                  .collect();
+        assert_eq!(names, expected);
+
+        let text = reader.find_section_by_name(".text").unwrap();
+        assert_eq!(reader.section_data(text).unwrap(), [0x90; 8]);
+        let bss = reader.find_section_by_name(".bss").unwrap();
+        assert!(reader.section_data(bss).unwrap().is_empty());
+        assert!(reader.section_data(reader.section_header(0).unwrap()).is_err());
+        assert!(reader.section_header(10).is_err());
+
+        let segment = reader.program_header(0).unwrap();
+        assert_eq!(reader.segment_data(segment).unwrap(), input);
+        assert_eq!(reader.program_iter().count(), 1);
+    }
+
+    #[test]
+    fn hash_table_accessors() {
+        let hash = le_bytes(&[2, 3, 1, 0, 0, 2, 0]);
+        let elf = mk_elf64(&[TestSection::new(".hash", 5, 0, hash)]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let table = match elf.find_section_by_name(".hash").unwrap().get_data(&elf) {
+            Ok(sections::SectionData::HashTable(table)) => table,
+            other => panic!("unexpected section data {:?}", other),
+        };
+        assert_eq!((table.bucket_count(), table.chain_count()), (2, 3));
+        assert_eq!((table.bucket(0), table.bucket(1), table.bucket(2)), (Some(1), Some(0), None));
+        assert_eq!((table.chain(1), table.chain(2), table.chain(3)), (Some(2), Some(0), None));
+        assert_eq!(table.get_bucket(0), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "object-compat")]
+    fn object_compat() {
+        use object::read::{ObjectSection, ObjectSymbol};
+
+        let mut symtab = vec![0; 24];
+        symtab.extend(mk_symbol64(1, 2, 1, 0x1000, 8));
+        let mut dynsym = vec![0; 24];
+        dynsym.extend(mk_symbol64(1, 2, 1, 0x1000, 8));
+        dynsym.extend(mk_symbol64(6, 2, 0, 0, 0));
+        let dynsym = TestSection { link: 4, ..TestSection::new(".dynsym", 11, 0, dynsym) };
+        let elf = mk_elf64(&[TestSection::new(".text", 1, sections::SHF_ALLOC, vec![0x90; 8]),
+                             TestSection::new(".strtab", 3, 0, b"\0main\0".to_vec()),
+                             TestSection { link: 2, ..TestSection::new(".symtab", 2, 0, symtab) },
+                             TestSection::new(".dynstr", 3, 0, b"\0main\0puts\0".to_vec()),
+                             dynsym]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let file = object_compat::parse(&elf).unwrap();
+
+        let header = elf.section_header(1).unwrap();
+        let section = object_compat::section(&file, 1).unwrap();
+        assert_eq!(section.name(), Ok(".text"));
+        assert_eq!((section.address(), section.size()), (header.address(), header.size()));
+        assert_eq!(section.data(), Ok(header.raw_data(&elf)));
+        let section = object_compat::section_for_header(&file, &elf, &header).unwrap();
+        assert_eq!(section.index(), object::read::SectionIndex(1));
+
+        let symtab = elf.find_section_by_name(".symtab").unwrap();
+        let main = match symtab.get_data(&elf) {
+            Ok(sections::SectionData::SymbolTable64(entries)) => &entries[1],
+            other => panic!("unexpected section data {:?}", other),
+        };
+        let symbol = object_compat::symbol(&file, &elf, symtab, main).unwrap();
+        assert_eq!((symbol.name(), symbol.address()), (Ok("main"), 0x1000));
+
+        let dynsym = elf.find_section_by_name(".dynsym").unwrap();
+        let puts = match dynsym.get_data(&elf) {
+            Ok(sections::SectionData::DynSymbolTable64(entries)) => &entries[2],
+            other => panic!("unexpected section data {:?}", other),
+        };
+        let symbol = object_compat::symbol(&file, &elf, dynsym, puts).unwrap();
+        assert_eq!(symbol.name(), Ok("puts"));
+        assert_eq!(object_compat::symbol_index(&elf, dynsym, puts),
+                   Some(object::read::SymbolIndex(2)));
+        assert!(object_compat::symbol(&file, &elf, symtab, puts).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn section_name_index() {
+        let elf = mk_elf64(&[TestSection::new(".text", 1, 0, vec![0; 4]),
+                             TestSection::new(".data", 1, 0, vec![0; 4]),
+                             TestSection::new(".text", 1, 0, vec![0; 4])]);
+        let elf = ElfFile::new(&elf).unwrap();
+
+        let index = sections::SectionNameIndex::new(&elf);
+        assert_eq!(index.index_of(".text"), Some(1));
+        assert_eq!(index.index_of(".data"), Some(2));
+        assert_eq!(index.index_of(".bss"), None);
+        assert_eq!(index.find(&elf, ".data").map(|s| s.offset()),
+                   elf.find_section_by_name(".data").map(|s| s.offset()));
+    }
+
+    #[test]
+    #[cfg(feature = "write")]
+    fn write_builder() {
+        let mut symtab = vec![0; 24];
+        symtab.extend(mk_symbol64(1, 1, 3, 0, 4));
+        let symtab = TestSection { link: 4, ..TestSection::new(".symtab", 2, 0, symtab) };
+        let mut elf = mk_elf64(&[TestSection::new(".text", 1, sections::SHF_ALLOC, vec![1; 4]),
+                                 TestSection::new(".comment", 1, 0, b"GCC\0".to_vec()),
+                                 TestSection::new(".data", 1, 0, vec![2; 4]),
+                                 TestSection::new(".strtab", 3, 0, b"\0x\0".to_vec()),
+                                 symtab]);
+        // Only load the headers, so that all the sections are moved.
+        elf[96..104].copy_from_slice(&120u64.to_le_bytes());
+        let file = ElfFile::new(&elf).unwrap();
+
+        let mut builder = write::Builder::new(&file).unwrap();
+        builder.remove_section(2).unwrap();
+        builder.set_name(3, ".mydata").unwrap();
+        builder.set_flags(1, sections::SHF_ALLOC | sections::SHF_EXECINSTR).unwrap();
+        assert_eq!(builder.remove_section(6), Err("Cannot remove the section header string table"));
+        assert_eq!(builder.set_flags(0, 0), Err("Cannot edit the null section"));
+        let out = builder.build().unwrap();
+
+        let new = ElfFile::new(&out).unwrap();
+        assert_eq!(header::sanity_check(&new), Ok(()));
+        let names: Vec<_> = new.section_iter().skip(1).map(|s| s.get_name(&new).unwrap()).collect();
+        assert_eq!(names, [".text", ".mydata", ".strtab", ".symtab", ".shstrtab"]);
+        let text = new.section_header(1).unwrap();
+        assert_eq!(text.flags(), sections::SHF_ALLOC | sections::SHF_EXECINSTR);
+        assert_eq!(text.raw_data(&new), &[1; 4]);
+        assert_eq!(new.section_header(2).unwrap().raw_data(&new), &[2; 4]);
+        let symtab = new.section_header(4).unwrap();
+        assert_eq!(symtab.link(), 3);
+        let symbols = symtab.get_data(&new).unwrap().symbols().unwrap();
+        assert_eq!(symbols.get(1).unwrap().shndx(), 2);
+        assert_eq!(symbols.get(1).unwrap().get_name(&new), Ok("x"));
+
+        // The symbol is defined in .data.
+        let mut builder = write::Builder::new(&file).unwrap();
+        builder.remove_section(3).unwrap();
+        assert_eq!(builder.build(), Err("Reference to a removed section"));
+
+        // Alignments (of .comment) which are not a power of two, or are larger
+        // than the file.
+        let sh_align = file.header.section_header_offset() as usize + 2 * 64 + 48;
+        for &bad_align in &[3u64, 1 << 44] {
+            let mut elf = elf.clone();
+            put(&mut elf, sh_align, &bad_align.to_le_bytes());
+            let file = ElfFile::new(&elf).unwrap();
+            assert_eq!(write::Builder::new(&file).unwrap().build(),
+                       Err("Invalid section alignment"));
+        }
+    }
+
+    #[test]
+    fn stack_and_relro_segments() {
+        let elf = mk_elf64(&[]);
+        let file = ElfFile::new(&elf).unwrap();
+        assert_eq!(file.has_executable_stack(), None);
+        assert!(!file.has_relro());
+
+        // Turn the Load segment into a GNU_STACK or GNU_RELRO one.
+        let with_segment = |type_: u32, flags: u32| {
+            let mut elf = elf.clone();
+            elf[64..68].copy_from_slice(&type_.to_le_bytes());
+            elf[68..72].copy_from_slice(&flags.to_le_bytes());
+            elf
+        };
+        let stack = program::TYPE_GNU_STACK;
+        let rwx = with_segment(stack, 7);
+        assert_eq!(ElfFile::new(&rwx).unwrap().has_executable_stack(), Some(true));
+        let rw = with_segment(stack, 6);
+        assert_eq!(ElfFile::new(&rw).unwrap().has_executable_stack(), Some(false));
+        let relro = with_segment(program::TYPE_GNU_RELRO, 4);
+        let file = ElfFile::new(&relro).unwrap();
+        assert!(file.has_relro());
+        assert_eq!(file.relro(),
+                   Some(RelroRange {
+                       virtual_addr: 0,
+                       mem_size: relro.len() as u64,
+                   }));
+        assert_eq!(ElfFile::new(&elf).unwrap().relro(), None);
+    }
+
+    #[test]
+    fn init_functions() {
+        let array = |addresses: &[u64]| {
+            addresses.iter().flat_map(|address| address.to_le_bytes().to_vec()).collect()
+        };
+        let elf = mk_elf64(&[TestSection::new(".init_array", 14, 3, array(&[0x30, 0x40])),
+                             TestSection::new(".fini_array", 15, 3, array(&[0x50, 0x60])),
+                             TestSection::new(".preinit_array", 16, 3, array(&[0x10]))]);
+        let elf = ElfFile::new(&elf).unwrap();
+        assert_eq!(elf.init_functions().collect::<Vec<_>>(), [0x10, 0x30, 0x40]);
+        assert_eq!(elf.fini_functions().collect::<Vec<_>>(), [0x50, 0x60]);
+
+        let data = elf.find_section_by_name(".fini_array").unwrap().get_data(&elf).unwrap();
+        assert_eq!(data.function_addresses().map(|addresses| addresses.count()), Some(2));
+        let shstrtab = elf.string_table().unwrap();
+        assert!(shstrtab.function_addresses().is_none());
+        assert_eq!(ElfFile::new(&mk_elf64(&[])).unwrap().init_functions().count(), 0);
+    }
+
+    #[test]
+    fn init_function() {
+        let elf = mk_dynamic_elf(b"\0", &[12, 0x1000, 13, 0x2000]);
+        let elf = ElfFile::new(&elf).unwrap();
+        assert_eq!(elf.init_function(), Some(0x1000));
+        assert_eq!(elf.fini_function(), Some(0x2000));
+
+        let elf = mk_dynamic_elf(b"\0", &[]);
+        let elf = ElfFile::new(&elf).unwrap();
+        assert_eq!((elf.init_function(), elf.fini_function()), (None, None));
+    }
+
+    // Rela64 entries for a JUMP_SLOT relocation against symbol 1, and a
+    // RELATIVE one.
+    fn mk_relocations64() -> Vec<u8> {
+        let mut relocations = vec![];
+        for &word in &[0x3018, 1 << 32 | 7, 0, 0x3020, 8, (-8i64) as u64] {
+            relocations.extend_from_slice(&(word as u64).to_le_bytes());
+        }
+        relocations
+    }
+
+    #[test]
+    fn plt_relocations() {
+        use sections::Relocation;
+
+        // .rela.plt is at offset (and address) 120, after the headers.
+        let mut dynamic = vec![];
+        for &word in &[23u64, 120, 2, 48, 20, 7, 0, 0] {
+            dynamic.extend_from_slice(&word.to_le_bytes());
+        }
+        let alloc = sections::SHF_ALLOC;
+        let elf = mk_elf64(&[TestSection::new(".rela.plt", 4, alloc, mk_relocations64()),
+                             TestSection::new(".dynamic", 6, alloc, dynamic)]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let rela_plt = elf.find_section_by_name(".rela.plt").unwrap();
+        assert_eq!(rela_plt.address(), 120);
+
+        let expected = [Relocation {
+                            offset: 0x3018,
+                            symbol_table_index: 1,
+                            type_: 7,
+                            addend: Some(0),
+                        },
+                        Relocation {
+                            offset: 0x3020,
+                            symbol_table_index: 0,
+                            type_: 8,
+                            addend: Some(-8i64 as u64),
+                        }];
+        assert_eq!(elf.plt_relocations().unwrap().collect::<Vec<_>>(), expected);
+        let relocations = rela_plt.get_data(&elf).unwrap().relocations().unwrap();
+        assert_eq!(relocations.len(), 2);
+        assert_eq!(relocations.get(1), Some(expected[1]));
+        assert!(ElfFile::new(&mk_dynamic_elf(b"\0", &[])).unwrap().plt_relocations().is_none());
+    }
+
+    #[test]
+    fn ifunc_resolver() {
+        use header::Machine;
+
+        // An IRELATIVE relocation (type 37) with its resolver at 0x1230,
+        // followed by the RELATIVE one from `mk_relocations64`.
+        let mut relocations = vec![];
+        for &word in &[0x3010u64, 37, 0x1230] {
+            relocations.extend_from_slice(&word.to_le_bytes());
+        }
+        relocations.extend_from_slice(&mk_relocations64()[24..]);
+        let elf = mk_elf64(&[TestSection::new(".rela.dyn", 4, sections::SHF_ALLOC, relocations)]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let data = elf.find_section_by_name(".rela.dyn").unwrap().get_data(&elf);
+        let relocations = match data {
+            Ok(sections::SectionData::Rela64(relocations)) => relocations,
+            _ => panic!("expected Rela64 data"),
+        };
+        let machine = elf.header.pt2.machine().as_machine();
+        assert_eq!(machine, Machine::X86_64);
+        assert_eq!(relocations[0].get_ifunc_resolver(machine), Some(0x1230));
+        assert_eq!(relocations[0].get_ifunc_resolver(Machine::AArch64), None);
+        assert_eq!(relocations[1].get_ifunc_resolver(machine), None);
+    }
+
+    #[test]
+    fn dynamic_rel_relocations() {
+        use sections::Relocation;
+
+        // An i386 shared object with two RELATIVE relocations and an R_386_32
+        // one against "puts", linked with `ld -m elf_i386 -shared`. The data
+        // segment's address is 0x10 past its file offset.
+        let elf = include_bytes!("../test-data/rel-dyn-i386").to_vec();
+        let elf = ElfFile::new(&elf).unwrap();
+        let relocation = |offset, symbol_table_index, type_| {
+            Relocation {
+                offset: offset,
+                symbol_table_index: symbol_table_index,
+                type_: type_,
+                addend: None,
+            }
+        };
+        assert_eq!(elf.dynamic_relocations().unwrap().collect::<Vec<_>>(),
+                   [relocation(0x184, 0, 8), relocation(0x188, 0, 8), relocation(0x18c, 1, 1)]);
+
+        let entries = match elf.find_section_by_name(".rel.dyn").unwrap().get_data(&elf) {
+            Ok(sections::SectionData::Rel32(entries)) => entries,
+            _ => panic!("expected Rel32 data"),
+        };
+        let addends = entries.iter().map(|rel| rel.get_implicit_addend(&elf)).collect::<Vec<_>>();
+        assert_eq!(addends, [Ok(0x100), Ok(0x104), Ok(0)]);
+    }
+
+    #[test]
+    fn dynamic_relocations() {
+        let mk_elf = |rela_ent: u64| {
+            // .rela.dyn is at offset (and address) 120, after the headers.
+            let mut dynamic = vec![];
+            for &word in &[7u64, 120, 8, 48, 9, rela_ent, 0, 0] {
+                dynamic.extend_from_slice(&word.to_le_bytes());
+            }
+            let alloc = sections::SHF_ALLOC;
+            mk_elf64(&[TestSection::new(".rela.dyn", 4, alloc, mk_relocations64()),
+                       TestSection::new(".dynamic", 6, alloc, dynamic)])
+        };
+        let elf = mk_elf(24);
+        let elf = ElfFile::new(&elf).unwrap();
+        let relocations: Vec<_> = elf.dynamic_relocations().unwrap().collect();
+        assert_eq!(relocations.len(), 2);
+        assert_eq!((relocations[1].type_, relocations[1].addend), (8, Some(-8i64 as u64)));
+        assert!(elf.plt_relocations().is_none());
+
+        let bad_entry_size = mk_elf(16);
+        assert!(ElfFile::new(&bad_entry_size).unwrap().dynamic_relocations().is_none());
+        let elf = mk_dynamic_elf(b"\0", &[]);
+        assert!(ElfFile::new(&elf).unwrap().dynamic_relocations().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn owned_elf_file() {
+        use owned::OwnedElfFile;
+
+        fn load() -> OwnedElfFile {
+            OwnedElfFile::new(mk_elf64(&[TestSection::new(".text", 1, 0, vec![1; 4])])).unwrap()
+        }
+        let owned = load();
+        let elf = owned.elf_file();
+        let text = elf.find_section_by_name(".text").unwrap();
+        assert_eq!(text.raw_data(&elf), &[1; 4]);
+        assert_eq!(owned.as_bytes().len(), elf.input.len());
+        assert!(OwnedElfFile::new(vec![0; 64]).is_err());
+    }
+
+    #[test]
+    fn gnu_hash_table() {
+        let mut dynsym = vec![0; 24];
+        dynsym.extend(mk_symbol64(1, 2, 1, 0x1000, 0));
+        dynsym.extend(mk_symbol64(6, 2, 1, 0x2000, 0));
+        let dynsym = TestSection { link: 1, ..TestSection::new(".dynsym", 11, 0, dynsym) };
+        let dynstr = TestSection::new(".dynstr", 3, 0, b"\0main\0puts\0".to_vec());
+        let elf = mk_elf64(&[dynstr, dynsym]);
+        let elf = ElfFile::new(&elf).unwrap();
+        let dynsym = match elf.find_section_by_name(".dynsym").unwrap().get_data(&elf) {
+            Ok(sections::SectionData::DynSymbolTable64(entries)) => entries,
+            other => panic!("unexpected section data {:?}", other),
+        };
+
+        // A single bucket holding both symbols, from index 1, and a bloom
+        // filter of `bloom_count` words with a shift of 6.
+        let hashes = [hash::gnu_hash("main"), hash::gnu_hash("puts")];
+        let mk_table = |bloom_count: u32, last_chain_end: u32| {
+            let bloom = hashes.iter()
+                .fold(0u64, |word, &h| word | (1 << (h % 64)) | (1 << ((h >> 6) % 64)));
+            let mut table = le_bytes(&[1, 1, bloom_count, 6]);
+            table.extend_from_slice(&bloom.to_le_bytes());
+            table.extend(le_bytes(&[1, hashes[0] & !1, (hashes[1] & !1) | last_chain_end]));
+            table
+        };
+
+        let data = mk_table(1, 1);
+        let table = hash::GnuHashTable::new(&data, 8).unwrap();
+        assert_eq!(table.validate(&elf, dynsym), Ok(()));
+        assert_eq!(table.symbol_count(), Some(3));
+
+        let data = mk_table(1, 0);
+        let table = hash::GnuHashTable::new(&data, 8).unwrap();
+        assert_eq!(table.validate(&elf, dynsym), Err("GNU hash chain does not end"));
+        assert_eq!(table.symbol_count(), None);
+
+        let data = mk_table(0, 1);
+        assert_eq!(hash::GnuHashTable::new(&data, 8).err(),
+                   Some("GNU hash table has no bloom filter"));
+        let data = mk_table(0x4000_0000, 1);
+        assert_eq!(hash::GnuHashTable::new(&data, 8).err(), Some("GNU hash table truncated"));
+        assert!(hash::GnuHashTable::new(&mk_table(1, 1), 3).is_err());
+    }
+
+    #[test]
+    fn hash_functions() {
+        assert_eq!(hash::hash("printf"), 0x077905a6);
+        assert_eq!(hash::gnu_hash(""), 0x00001505);
+        assert_eq!(hash::gnu_hash("printf"), 0x156b2bb8);
+    }
 }
@@ -0,0 +1,70 @@
+//! Handing data found with xmas-elf to tools built on the `object` crate.
+//!
+//! `object`'s reader traits are only implemented by its own types, so rather
+//! than implementing them here, this parses the same input with `object` and
+//! maps xmas-elf's section headers and symbol table entries onto `object`'s
+//! sections and symbols, by index. Both borrow the input, so nothing is
+//! copied, and the `object` section has the usual `name`, `address`, `size`
+//! and `data` accessors.
+
+use object::read::{File, Object, ObjectSymbolTable, Section, SectionIndex, Symbol, SymbolIndex};
+use object::Result;
+
+use ElfFile;
+use sections::{SectionHeader, ShType};
+use symbol_table::{self, Entry};
+
+/// Parse the input of `elf_file` with `object`.
+pub fn parse<'a>(elf_file: &ElfFile<'a>) -> Result<File<'a>> {
+    File::parse(elf_file.input)
+}
+
+/// The `object` section with the given index, i.e., the same section as
+/// `ElfFile::section_header(index)`.
+pub fn section<'data, 'file>(file: &'file File<'data>,
+                             index: u16)
+                             -> Result<Section<'data, 'file>> {
+    file.section_by_index(SectionIndex(index as usize))
+}
+
+/// The `object` index of `header`, which must have been read from `elf_file`.
+pub fn section_index<'a>(elf_file: &ElfFile<'a>,
+                        header: &SectionHeader<'a>)
+                        -> Option<SectionIndex> {
+    elf_file.section_index(header).map(|index| SectionIndex(index as usize))
+}
+
+/// The `object` section for `header`, which must have been read from
+/// `elf_file`, the file `file` was parsed from.
+pub fn section_for_header<'data, 'file>(file: &'file File<'data>,
+                                        elf_file: &ElfFile<'data>,
+                                        header: &SectionHeader<'data>)
+                                        -> Option<Section<'data, 'file>> {
+    section_index(elf_file, header).and_then(|index| file.section_by_index(index).ok())
+}
+
+/// The `object` index of `entry`, which must have been read from the symbol
+/// table `symtab_section` of `elf_file`.
+pub fn symbol_index<'a, E: Entry>(elf_file: &ElfFile<'a>,
+                                  symtab_section: SectionHeader<'a>,
+                                  entry: &E)
+                                  -> Option<SymbolIndex> {
+    let table = symtab_section.try_raw_data(elf_file).ok()?;
+    symbol_table::index_in_table(entry, table).map(SymbolIndex)
+}
+
+/// The `object` symbol for `entry`, which must have been read from the symbol
+/// table `symtab_section` (a `SymTab` or `DynSym` section) of `elf_file`, the
+/// file `file` was parsed from.
+pub fn symbol<'data, 'file, E: Entry>(file: &'file File<'data>,
+                                      elf_file: &ElfFile<'data>,
+                                      symtab_section: SectionHeader<'data>,
+                                      entry: &E)
+                                      -> Option<Symbol<'data, 'file>> {
+    let index = symbol_index(elf_file, symtab_section, entry)?;
+    match symtab_section.get_type() {
+        Ok(ShType::SymTab) => file.symbol_by_index(index).ok(),
+        Ok(ShType::DynSym) => file.dynamic_symbol_table()?.symbol_by_index(index).ok(),
+        _ => None,
+    }
+}
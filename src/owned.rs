@@ -0,0 +1,88 @@
+//! An ELF file which owns its bytes, for when the parsed file must outlive the
+//! function which read it (e.g., into a `Vec<u8>` from `std::fs::read`).
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::slice;
+
+use ElfFile;
+
+/// Owns the bytes of an ELF file and lends out `ElfFile` views of them. The
+/// header is checked once, in `new`; `elf_file` only re-reads it, which is
+/// cheap.
+#[derive(Debug)]
+pub struct OwnedElfFile {
+    storage: Storage,
+}
+
+#[derive(Debug)]
+enum Storage {
+    Bytes(Vec<u8>),
+    // A copy of bytes which were not aligned well enough to read headers from
+    // in place.
+    Words(AlignedBuf),
+}
+
+impl OwnedElfFile {
+    /// `data` is used as is if it is 8-byte aligned (as heap allocations
+    /// usually are), and copied otherwise.
+    pub fn new(data: Vec<u8>) -> Result<OwnedElfFile, &'static str> {
+        let storage = if (data.as_ptr() as usize).is_multiple_of(align_of::<u64>()) {
+            Storage::Bytes(data)
+        } else {
+            Storage::Words(AlignedBuf::copy_from(&data))
+        };
+        let result = OwnedElfFile { storage };
+        let _ = ElfFile::new(result.as_bytes())?;
+        Ok(result)
+    }
+
+    pub fn elf_file<'a>(&'a self) -> ElfFile<'a> {
+        // Already checked in `new`.
+        ElfFile::new(self.as_bytes()).unwrap()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self.storage {
+            Storage::Bytes(ref bytes) => bytes,
+            Storage::Words(ref buf) => buf.bytes(),
+        }
+    }
+}
+
+// Bytes stored with 8-byte alignment, so that headers can be read from them in
+// place. Also used by `reader::ElfReader`.
+#[derive(Debug)]
+pub(crate) struct AlignedBuf {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl AlignedBuf {
+    /// `len` zero bytes.
+    pub(crate) fn new(len: usize) -> AlignedBuf {
+        AlignedBuf {
+            words: vec![0u64; len.div_ceil(8)],
+            len,
+        }
+    }
+
+    pub(crate) fn copy_from(data: &[u8]) -> AlignedBuf {
+        let mut result = AlignedBuf::new(data.len());
+        result.bytes_mut().copy_from_slice(data);
+        result
+    }
+
+    pub(crate) fn bytes(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.words.as_ptr() as *const u8, self.len) }
+    }
+
+    pub(crate) fn bytes_mut(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.words.as_mut_ptr() as *mut u8, self.len) }
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+}
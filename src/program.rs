@@ -1,8 +1,8 @@
-use {ElfFile, P32, P64};
-use zero::{read, read_array, Pod};
+use {ElfFile, P32, P64, try_read_array};
+use zero::{read, read_str, Pod};
 use header::{Class, Header};
 use dynamic::Dynamic;
-use sections::NoteHeader;
+use sections::{NoteHeader, NoteIter};
 
 use core::mem;
 use core::fmt;
@@ -64,7 +64,7 @@ pub struct ProgramHeader32 {
     physical_addr: u32,
     file_size: u32,
     mem_size: u32,
-    flags: Flags,
+    flags: SegmentFlags,
     align: u32,
 }
 
@@ -74,7 +74,7 @@ unsafe impl Pod for ProgramHeader32 {}
 #[repr(C)]
 pub struct ProgramHeader64 {
     type_: Type_,
-    flags: Flags,
+    flags: SegmentFlags,
     offset: u64,
     virtual_addr: u64,
     physical_addr: u64,
@@ -111,13 +111,44 @@ impl<'a> ProgramHeader<'a> {
         }
     }
 
+    /// The bytes of the segment which are backed by the file, i.e., `file_size`
+    /// bytes from `offset`. For loadable segments `mem_size` may be larger;
+    /// the rest of the segment (e.g., `.bss`) is zero filled in memory and is
+    /// not part of this slice.
+    ///
+    /// Panics if the segment is null or its data lies outside the file; see
+    /// `try_raw_data`.
+    pub fn raw_data(&self, elf_file: &ElfFile<'a>) -> &'a [u8] {
+        match *self {
+            ProgramHeader::Ph32(ph) => ph.raw_data(elf_file),
+            ProgramHeader::Ph64(ph) => ph.raw_data(elf_file),
+        }
+    }
+
+    pub fn try_raw_data(&self, elf_file: &ElfFile<'a>) -> Result<&'a [u8], &'static str> {
+        match *self {
+            ProgramHeader::Ph32(ph) => ph.try_raw_data(elf_file),
+            ProgramHeader::Ph64(ph) => ph.try_raw_data(elf_file),
+        }
+    }
+
+    /// Whether the segment is mapped into memory by the loader, as opposed to
+    /// describing metadata such as notes or the dynamic table.
+    pub fn is_loadable(&self) -> bool {
+        self.get_type().map(|typ| typ.is_loadable()).unwrap_or(false)
+    }
+
     getter!(align, u64);
     getter!(file_size, u64);
     getter!(mem_size, u64);
     getter!(offset, u64);
     getter!(physical_addr, u64);
     getter!(virtual_addr, u64);
-    getter!(flags, Flags);
+    getter!(flags, SegmentFlags);
+
+    pub fn get_flags(&self) -> SegmentFlags {
+        self.flags()
+    }
 }
 
 impl<'a> fmt::Display for ProgramHeader<'a> {
@@ -136,39 +167,62 @@ macro_rules! ph_impl {
             }
 
             pub fn get_data<'a>(&self, elf_file: &ElfFile<'a>) -> Result<SegmentData<'a>, &'static str> {
-                self.get_type().map(|typ| match typ {
+                Ok(match self.get_type()? {
                     Type::Null => SegmentData::Empty,
-                    Type::Load | Type::Interp | Type::ShLib | Type::Phdr | Type::Tls |
+                    Type::Load | Type::ShLib | Type::Phdr | Type::Tls |
                     Type::GnuRelro | Type::OsSpecific(_) | Type::ProcessorSpecific(_) => {
-                        SegmentData::Undefined(self.raw_data(elf_file))
+                        SegmentData::Undefined(self.try_raw_data(elf_file)?)
+                    }
+                    Type::Interp => {
+                        SegmentData::Interp(read_str(self.try_raw_data(elf_file)?)?)
                     }
                     Type::Dynamic => {
-                        let data = self.raw_data(elf_file);
+                        let data = self.try_raw_data(elf_file)?;
                         match elf_file.header.pt1.class() {
-                            Class::ThirtyTwo => SegmentData::Dynamic32(read_array(data)),
-                            Class::SixtyFour => SegmentData::Dynamic64(read_array(data)),
+                            Class::ThirtyTwo => SegmentData::Dynamic32(try_read_array(data)?),
+                            Class::SixtyFour => SegmentData::Dynamic64(try_read_array(data)?),
                             Class::None | Class::Other(_) => unreachable!(),
                         }
                     }
                     Type::Note => {
-                        let data = self.raw_data(elf_file);
+                        let data = self.try_raw_data(elf_file)?;
+                        if data.len() < 12 {
+                            return Err("Note segment too short");
+                        }
+                        let header: &'a NoteHeader = read(&data[0..12]);
+                        let index = &data[12..];
                         match elf_file.header.pt1.class() {
-                            Class::ThirtyTwo => unimplemented!(),
-                            Class::SixtyFour => {
-                                let header: &'a NoteHeader = read(&data[0..12]);
-                                let index = &data[12..];
-                                SegmentData::Note64(header, index)
-                            }
+                            Class::ThirtyTwo => SegmentData::Note32(header, index),
+                            Class::SixtyFour => SegmentData::Note64(header, index),
                             Class::None | Class::Other(_) => unreachable!(),
                         }
                     }
                 })
             }
 
+            /// The `file_size` bytes of the segment stored in the file. This may be
+            /// shorter than `mem_size`.
             pub fn raw_data<'a>(&self, elf_file: &ElfFile<'a>) -> &'a [u8] {
                 assert!(self.get_type().map(|typ| typ != Type::Null).unwrap_or(false));
                 &elf_file.input[self.offset as usize..(self.offset + self.file_size) as usize]
             }
+
+            /// Like `raw_data`, but returns an error rather than panicking if the
+            /// segment is null or its data lies outside the file.
+            pub fn try_raw_data<'a>(&self,
+                                    elf_file: &ElfFile<'a>)
+                                    -> Result<&'a [u8], &'static str> {
+                if self.get_type()? == Type::Null {
+                    return Err("Attempt to get data of null segment");
+                }
+                let start = self.offset as u64;
+                let end = start.checked_add(self.file_size as u64)
+                               .ok_or("Segment data out of range")?;
+                if end > elf_file.input.len() as u64 {
+                    return Err("Segment data out of range");
+                }
+                Ok(&elf_file.input[start as usize..end as usize])
+            }
         }
 
         impl fmt::Display for $ph {
@@ -191,10 +245,98 @@ macro_rules! ph_impl {
 ph_impl!(ProgramHeader32);
 ph_impl!(ProgramHeader64);
 
+/// The thread-local storage template, from the `Tls` segment. Each thread's
+/// TLS block is `mem_size` bytes aligned to `align`: the first `file_size`
+/// bytes are copied from `file_offset` (`.tdata`), and the rest are
+/// zero-filled (`.tbss`). The values of `STT_TLS` symbols are offsets into
+/// this block.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TlsInfo {
+    pub file_offset: u64,
+    pub file_size: u64,
+    pub mem_size: u64,
+    pub align: u64,
+}
+
+/// The range made read-only after relocation, from the `GnuRelro` segment.
+/// With lazy binding this excludes the GOT entries used by the PLT ("partial
+/// RELRO"); with `DT_BIND_NOW` it covers them too ("full RELRO").
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RelroRange {
+    pub virtual_addr: u64,
+    pub mem_size: u64,
+}
+
+/// Why `ElfFile::validate_alloc_coverage` failed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CoverageError<'a> {
+    /// The named allocated section is not within any `Load` segment.
+    Uncovered(&'a str),
+    /// A section header could not be read.
+    Malformed(&'static str),
+}
+
+/// A `Load` segment, as a loader sees it: `file_size` bytes from
+/// `file_offset` are copied to `virtual_addr`, and the rest of the `mem_size`
+/// bytes (e.g., `.bss`) are zero-filled.
 #[derive(Copy, Clone, Debug)]
-pub struct Flags(pub u32);
+pub struct LoadSegment<'a> {
+    header: ProgramHeader<'a>,
+    data: Result<&'a [u8], &'static str>,
+}
+
+impl<'a> LoadSegment<'a> {
+    pub fn new(header: ProgramHeader<'a>, elf_file: &ElfFile<'a>) -> LoadSegment<'a> {
+        let data = if header.file_size() > header.mem_size() {
+            Err("Segment file size exceeds its memory size")
+        } else {
+            header.try_raw_data(elf_file)
+        };
+        LoadSegment {
+            header,
+            data,
+        }
+    }
+
+    pub fn header(&self) -> ProgramHeader<'a> {
+        self.header
+    }
+
+    pub fn virtual_addr(&self) -> u64 {
+        self.header.virtual_addr()
+    }
+
+    pub fn mem_size(&self) -> u64 {
+        self.header.mem_size()
+    }
+
+    pub fn file_offset(&self) -> u64 {
+        self.header.offset()
+    }
+
+    pub fn file_size(&self) -> u64 {
+        self.header.file_size()
+    }
 
-impl Flags {
+    pub fn flags(&self) -> SegmentFlags {
+        self.header.flags()
+    }
+
+    /// The bytes to copy to `virtual_addr`, or an error if they lie outside
+    /// the file or are larger than `mem_size`.
+    pub fn data(&self) -> Result<&'a [u8], &'static str> {
+        self.data
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct SegmentFlags(pub u32);
+
+/// The name `SegmentFlags` was introduced later; this alias keeps older code
+/// compiling.
+pub type Flags = SegmentFlags;
+
+impl SegmentFlags {
     pub fn is_execute(&self) -> bool {
         self.0 & FLAG_X == FLAG_X
     }
@@ -208,16 +350,38 @@ impl Flags {
     }
 }
 
-impl fmt::Display for Flags {
+// In the style of `readelf -l`, e.g., `R-X`.
+impl fmt::Display for SegmentFlags {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}{}{}",
-               if self.0 & FLAG_X == FLAG_X { 'X' } else { ' ' },
-               if self.0 & FLAG_W == FLAG_W { 'W' } else { ' ' },
-               if self.0 & FLAG_R == FLAG_R { 'R' } else { ' ' })
+               if self.is_read() { 'R' } else { '-' },
+               if self.is_write() { 'W' } else { '-' },
+               if self.is_execute() { 'X' } else { '-' })
     }
 }
 
-impl fmt::LowerHex for Flags {
+impl fmt::Debug for SegmentFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const NAMES: [(u32, &str); 3] = [(FLAG_R, "PF_R"), (FLAG_W, "PF_W"), (FLAG_X, "PF_X")];
+
+        write!(f, "SegmentFlags(")?;
+        let mut rest = self.0;
+        let mut first = true;
+        for &(flag, name) in NAMES.iter() {
+            if rest & flag == flag {
+                write!(f, "{}{}", if first { "" } else { " | " }, name)?;
+                rest &= !flag;
+                first = false;
+            }
+        }
+        if rest != 0 || first {
+            write!(f, "{}{:#x}", if first { "" } else { " | " }, rest)?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl fmt::LowerHex for SegmentFlags {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let val = self.0;
 
@@ -243,6 +407,15 @@ pub enum Type {
     ProcessorSpecific(u32),
 }
 
+impl Type {
+    /// Only `Load` segments are mapped by the loader. Other segments (e.g.,
+    /// `Note`, `Dynamic` or `PT_GNU_EH_FRAME`) describe parts of the file
+    /// which are usually within some `Load` segment.
+    pub fn is_loadable(&self) -> bool {
+        *self == Type::Load
+    }
+}
+
 impl Type_ {
     fn as_type(&self) -> Result<Type, &'static str> {
         match self.0 {
@@ -274,16 +447,34 @@ pub enum SegmentData<'a> {
     Undefined(&'a [u8]),
     Dynamic32(&'a [Dynamic<P32>]),
     Dynamic64(&'a [Dynamic<P64>]),
-    // Note32 uses 4-byte words, which I'm not sure how to manage.
+    // Both classes use 4-byte words for notes in practice (despite the spec).
     // The pointer is to the start of the name field in the note.
-    Note64(&'a NoteHeader, &'a [u8]), /* TODO Interp and Phdr should probably be defined some how, but I can't find the details. */
+    Note32(&'a NoteHeader, &'a [u8]),
+    Note64(&'a NoteHeader, &'a [u8]),
+    // The path of the program interpreter.
+    Interp(&'a str),
+    /* TODO Phdr should probably be defined some how, but I can't find the details. */
+}
+
+impl<'a> SegmentData<'a> {
+    /// Iterate over all the notes in a note segment.
+    pub fn notes(&self) -> Option<NoteIter<'a>> {
+        match *self {
+            SegmentData::Note32(header, data) |
+            SegmentData::Note64(header, data) => Some(NoteIter::new(header, data)),
+            _ => None,
+        }
+    }
 }
 
 pub const TYPE_LOOS: u32 = 0x60000000;
 pub const TYPE_HIOS: u32 = 0x6fffffff;
 pub const TYPE_LOPROC: u32 = 0x70000000;
 pub const TYPE_HIPROC: u32 = 0x7fffffff;
+pub const TYPE_GNU_EH_FRAME: u32 = TYPE_LOOS + 0x474e550;
+pub const TYPE_GNU_STACK: u32 = TYPE_LOOS + 0x474e551;
 pub const TYPE_GNU_RELRO: u32 = TYPE_LOOS + 0x474e552;
+pub const TYPE_GNU_PROPERTY: u32 = TYPE_LOOS + 0x474e553;
 
 pub const FLAG_X: u32 = 0x1;
 pub const FLAG_W: u32 = 0x2;
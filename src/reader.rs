@@ -0,0 +1,245 @@
+//! Reading ELF files through `Read + Seek` rather than from a slice.
+//!
+//! `ElfFile` needs the whole file in memory (or mapped). That is wasteful for
+//! very large files such as core dumps, where usually only the headers and a
+//! few segments are of interest. `ElfReader` reads the ELF header, the program
+//! and section header tables and the section header string table up front,
+//! and everything else only when asked for.
+//!
+//! The tradeoffs are that every data access is a seek and a read (and an
+//! allocation), so scanning many small sections is slower than with `ElfFile`,
+//! and that data is returned as owned buffers rather than borrowed slices, so
+//! the typed views (`SectionData` and friends) are not available. A common
+//! pattern is to use `ElfReader` to find the interesting parts of a large file,
+//! then read them with `read_at`.
+
+use std::cell::RefCell;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::vec;
+use std::vec::Vec;
+
+use {P32, P64};
+use header::{self, Class, Header};
+use program::{ProgramHeader, ProgramHeader32, ProgramHeader64};
+use sections::{self, SectionHeader, SectionHeader_, ShType};
+use owned::AlignedBuf;
+use zero::{read, read_str};
+
+/// An ELF file which is read on demand from `R`, e.g., a `std::fs::File`.
+#[derive(Debug)]
+pub struct ElfReader<R> {
+    reader: RefCell<R>,
+    header: AlignedBuf,
+    program_headers: AlignedBuf,
+    section_headers: AlignedBuf,
+    shstr_table: Vec<u8>,
+}
+
+impl<R: Read + Seek> ElfReader<R> {
+    /// Read and check the ELF header and the header tables.
+    pub fn new(reader: R) -> io::Result<ElfReader<R>> {
+        let mut reader = reader;
+        let header = AlignedBuf::read_up_to(&mut reader, 0, 64)?;
+        let (ph_table, sh_table, shstr_index) = {
+            let header = header::parse_header(header.bytes()).map_err(invalid_data)?;
+            let pt2 = &header.pt2;
+            let ph_table = (pt2.ph_offset(), pt2.ph_entry_size() as u64 * pt2.ph_count() as u64);
+            let sh_table = (pt2.sh_offset(), pt2.sh_entry_size() as u64 * pt2.sh_count() as u64);
+            (ph_table, sh_table, pt2.sh_str_index())
+        };
+
+        let mut result = ElfReader {
+            header,
+            program_headers: AlignedBuf::read_exact(&mut reader, ph_table.0, ph_table.1)?,
+            section_headers: AlignedBuf::read_exact(&mut reader, sh_table.0, sh_table.1)?,
+            shstr_table: Vec::new(),
+            reader: RefCell::new(reader),
+        };
+        result.check_entry_sizes()?;
+
+        let shstr_index = if shstr_index == sections::SHN_XINDEX {
+            result.section_header(0)?.link()
+        } else {
+            shstr_index as u32
+        };
+        if shstr_index != sections::SHN_UNDEF as u32 {
+            let table = result.section_header_at(shstr_index)?;
+            result.shstr_table = result.section_data(table)?;
+        }
+        Ok(result)
+    }
+
+    pub fn header<'b>(&'b self) -> Header<'b> {
+        // Already checked in `new`.
+        header::parse_header(self.header.bytes()).unwrap()
+    }
+
+    pub fn program_header<'b>(&'b self, index: u16) -> io::Result<ProgramHeader<'b>> {
+        let entry_size = self.header().pt2.ph_entry_size() as usize;
+        let start = index as usize * entry_size;
+        let input = self.program_headers
+                        .bytes()
+                        .get(start..start + entry_size)
+                        .ok_or_else(|| invalid_data("Program header index out of range"))?;
+        Ok(match self.header().pt1.class() {
+            Class::ThirtyTwo => {
+                ProgramHeader::Ph32(read(&input[..size_of::<ProgramHeader32>()]))
+            }
+            Class::SixtyFour => {
+                ProgramHeader::Ph64(read(&input[..size_of::<ProgramHeader64>()]))
+            }
+            Class::None | Class::Other(_) => unreachable!(),
+        })
+    }
+
+    pub fn program_iter<'b>(&'b self) -> impl Iterator<Item = ProgramHeader<'b>> + 'b {
+        (0..self.header().pt2.ph_count()).filter_map(move |i| self.program_header(i).ok())
+    }
+
+    pub fn section_header<'b>(&'b self, index: u16) -> io::Result<SectionHeader<'b>> {
+        self.section_header_at(index as u32)
+    }
+
+    pub fn section_iter<'b>(&'b self) -> impl Iterator<Item = SectionHeader<'b>> + 'b {
+        (0..self.header().pt2.sh_count()).filter_map(move |i| self.section_header(i).ok())
+    }
+
+    pub fn section_name(&self, header: SectionHeader) -> io::Result<&str> {
+        let table = self.shstr_table
+                        .get(header.name() as usize..)
+                        .ok_or_else(|| invalid_data("String table index out of range"))?;
+        read_str(table).map_err(invalid_data)
+    }
+
+    pub fn find_section_by_name<'b>(&'b self, name: &str) -> Option<SectionHeader<'b>> {
+        self.section_iter().find(|sect| self.section_name(*sect).ok() == Some(name))
+    }
+
+    /// Read the contents of a section from the file. This is empty for
+    /// `NoBits` sections.
+    pub fn section_data(&self, header: SectionHeader) -> io::Result<Vec<u8>> {
+        match header.get_type().map_err(invalid_data)? {
+            ShType::Null => Err(invalid_data("Attempt to get data of null section")),
+            ShType::NoBits => Ok(Vec::new()),
+            _ => self.read_vec(header.offset(), header.size()),
+        }
+    }
+
+    /// Read the part of a segment which is backed by the file (`file_size`
+    /// bytes, which may be less than `mem_size`).
+    pub fn segment_data(&self, header: ProgramHeader) -> io::Result<Vec<u8>> {
+        self.read_vec(header.offset(), header.file_size())
+    }
+
+    /// Fill `buf` from the file, starting at `offset`.
+    pub fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<()> {
+        let mut reader = self.reader.borrow_mut();
+        let _ = reader.seek(SeekFrom::Start(offset))?;
+        reader.read_exact(buf)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader.into_inner()
+    }
+
+    fn read_vec(&self, offset: u64, size: u64) -> io::Result<Vec<u8>> {
+        // Check the size before allocating, in case it is bogus.
+        let end = offset.checked_add(size).ok_or_else(|| invalid_data("Data out of range"))?;
+        let len = self.reader.borrow_mut().seek(SeekFrom::End(0))?;
+        if end > len {
+            return Err(invalid_data("Data out of range"));
+        }
+        let mut result = vec![0; size as usize];
+        self.read_at(offset, &mut result)?;
+        Ok(result)
+    }
+
+    fn section_header_at<'b>(&'b self, index: u32) -> io::Result<SectionHeader<'b>> {
+        let entry_size = self.header().pt2.sh_entry_size() as usize;
+        let start = index as usize * entry_size;
+        let input = self.section_headers
+                        .bytes()
+                        .get(start..start + entry_size)
+                        .ok_or_else(|| invalid_data("Section header index out of range"))?;
+        Ok(match self.header().pt1.class() {
+            Class::ThirtyTwo => {
+                SectionHeader::Sh32(read(&input[..size_of::<SectionHeader_<P32>>()]))
+            }
+            Class::SixtyFour => {
+                SectionHeader::Sh64(read(&input[..size_of::<SectionHeader_<P64>>()]))
+            }
+            Class::None | Class::Other(_) => unreachable!(),
+        })
+    }
+
+    fn check_entry_sizes(&self) -> io::Result<()> {
+        let header = self.header();
+        let (ph_size, sh_size) = match header.pt1.class() {
+            Class::ThirtyTwo => {
+                (size_of::<ProgramHeader32>(), size_of::<SectionHeader_<P32>>())
+            }
+            Class::SixtyFour => {
+                (size_of::<ProgramHeader64>(), size_of::<SectionHeader_<P64>>())
+            }
+            Class::None | Class::Other(_) => unreachable!(),
+        };
+        let pt2 = &header.pt2;
+        if pt2.ph_count() > 0 && (pt2.ph_entry_size() as usize) < ph_size {
+            return Err(invalid_data("program header size mismatch"));
+        }
+        if pt2.sh_count() > 0 && (pt2.sh_entry_size() as usize) < sh_size {
+            return Err(invalid_data("section header size mismatch"));
+        }
+        // Keep entries aligned within the buffers.
+        if (!pt2.ph_entry_size().is_multiple_of(8) && pt2.ph_count() > 1) ||
+           (!pt2.sh_entry_size().is_multiple_of(8) && pt2.sh_count() > 1) {
+            return Err(invalid_data("header table entry size is not a multiple of 8"));
+        }
+        Ok(())
+    }
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+// Bytes read from the file are kept aligned so that headers can be read from
+// them in place, as they are from `ElfFile::input`.
+impl AlignedBuf {
+    fn read_exact<R: Read + Seek>(reader: &mut R,
+                                  offset: u64,
+                                  len: u64)
+                                  -> io::Result<AlignedBuf> {
+        if len == 0 {
+            return Ok(AlignedBuf::new(0));
+        }
+        let end = offset.checked_add(len).ok_or_else(|| invalid_data("Data out of range"))?;
+        if end > reader.seek(SeekFrom::End(0))? {
+            return Err(invalid_data("Header table out of range"));
+        }
+        let mut result = AlignedBuf::new(len as usize);
+        let _ = reader.seek(SeekFrom::Start(offset))?;
+        reader.read_exact(result.bytes_mut())?;
+        Ok(result)
+    }
+
+    // Like `read_exact`, but stops early at the end of the file.
+    fn read_up_to<R: Read + Seek>(reader: &mut R,
+                                  offset: u64,
+                                  len: usize)
+                                  -> io::Result<AlignedBuf> {
+        let mut result = AlignedBuf::new(len);
+        let _ = reader.seek(SeekFrom::Start(offset))?;
+        let mut read = 0;
+        while read < len {
+            match reader.read(&mut result.bytes_mut()[read..]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        result.truncate(read);
+        Ok(result)
+    }
+}
@@ -2,6 +2,8 @@
 use std::borrow::Cow;
 #[cfg(feature = "compression")]
 use std::vec::Vec;
+#[cfg(feature = "alloc")]
+use alloc::collections::BTreeMap;
 
 use core::fmt;
 use core::mem;
@@ -10,10 +12,10 @@ use core::slice;
 #[cfg(feature = "compression")]
 use flate2::{Decompress, FlushDecompress};
 
-use {P32, P64, ElfFile};
-use header::{Header, Class};
+use {P32, P64, ElfFile, try_read_array};
+use header::{Header, Class, Data, Machine};
 use zero::{read, read_array, read_str, read_strs_to_null, StrReaderIterator, Pod};
-use symbol_table;
+use symbol_table::{self, Entry, SymbolSlice};
 use dynamic::Dynamic;
 use hash::HashTable;
 
@@ -24,21 +26,45 @@ pub fn parse_section_header<'a>(input: &'a [u8],
     // Trying to get index 0 (SHN_UNDEF) is also probably an error, but it is a legitimate section.
     assert!(index < SHN_LORESERVE,
             "Attempt to get section for a reserved index");
+    parse_section_header_at(input, header, index as u32)
+}
 
-    let start = (index as u64 * header.pt2.sh_entry_size() as u64 +
-                 header.pt2.sh_offset() as u64) as usize;
-    let end = start + header.pt2.sh_entry_size() as usize;
+// Like `parse_section_header`, but the index may lie in the reserved range, as
+// happens with extended section numbering.
+pub(crate) fn parse_section_header_at<'a>(input: &'a [u8],
+                                          header: Header<'a>,
+                                          index: u32)
+                                          -> Result<SectionHeader<'a>, &'static str> {
+    let entry_size = header.pt2.sh_entry_size() as u64;
+    let start = (index as u64 * entry_size).checked_add(header.pt2.sh_offset())
+                                           .ok_or("Section header offset overflows")?;
+    let end = start.checked_add(entry_size).ok_or("Section header offset overflows")?;
+    if end > input.len() as u64 {
+        return Err("Section header out of range");
+    }
+    let input = &input[start as usize..end as usize];
 
-    Ok(match header.pt1.class() {
+    // Check what `read` would otherwise assert.
+    let (size, align) = match header.pt1.class() {
         Class::ThirtyTwo => {
-            let header: &'a SectionHeader_<P32> = read(&input[start..end]);
-            SectionHeader::Sh32(header)
+            (size_of::<SectionHeader_<P32>>(), align_of::<SectionHeader_<P32>>())
         }
         Class::SixtyFour => {
-            let header: &'a SectionHeader_<P64> = read(&input[start..end]);
-            SectionHeader::Sh64(header)
+            (size_of::<SectionHeader_<P64>>(), align_of::<SectionHeader_<P64>>())
         }
         Class::None | Class::Other(_) => unreachable!(),
+    };
+    if input.len() < size {
+        return Err("Section header entry size too small");
+    }
+    if !(input.as_ptr() as usize).is_multiple_of(align) {
+        return Err("Section header is misaligned");
+    }
+
+    Ok(match header.pt1.class() {
+        Class::ThirtyTwo => SectionHeader::Sh32(read(input)),
+        Class::SixtyFour => SectionHeader::Sh64(read(input)),
+        Class::None | Class::Other(_) => unreachable!(),
     })
 }
 
@@ -53,13 +79,53 @@ impl<'b, 'a> Iterator for SectionIter<'b, 'a> {
 
     fn next(&mut self) -> Option<Self::Item> {
         let count = self.file.header.pt2.sh_count();
-        if self.next_index >= count {
+        if self.next_index >= count || self.next_index >= SHN_LORESERVE {
             return None;
         }
 
-        let result = self.file.section_header(self.next_index);
-        self.next_index += 1;
-        result.ok()
+        match self.file.section_header(self.next_index) {
+            Ok(header) => {
+                self.next_index += 1;
+                Some(header)
+            }
+            Err(_) => {
+                // The rest of the table is out of range or malformed too.
+                self.next_index = count;
+                None
+            }
+        }
+    }
+}
+
+/// Section indices keyed by name, for files with many sections where looking
+/// each one up with `ElfFile::find_section_by_name` (which compares the name of
+/// every section in turn) is too slow. If several sections have the same name,
+/// the first is used, as with `find_section_by_name`.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct SectionNameIndex<'a> {
+    indices: BTreeMap<&'a str, u16>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> SectionNameIndex<'a> {
+    pub fn new(elf_file: &ElfFile<'a>) -> SectionNameIndex<'a> {
+        let mut indices = BTreeMap::new();
+        // Indices from `SHN_LORESERVE` on cannot be passed to `section_header`.
+        for (index, sect) in elf_file.section_iter().enumerate().take(SHN_LORESERVE as usize) {
+            if let Ok(name) = sect.get_name(elf_file) {
+                let _ = indices.entry(name).or_insert(index as u16);
+            }
+        }
+        SectionNameIndex { indices }
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<u16> {
+        self.indices.get(name).cloned()
+    }
+
+    pub fn find(&self, elf_file: &ElfFile<'a>, name: &str) -> Option<SectionHeader<'a>> {
+        self.index_of(name).and_then(|index| elf_file.section_header(index).ok())
     }
 }
 
@@ -105,28 +171,87 @@ impl<'a> SectionHeader<'a> {
         self.type_().as_sh_type()
     }
 
+    /// The raw bytes of this header, as they appear in the section header
+    /// table. For files which pass `sanity_check`, this is `sh_entry_size`
+    /// bytes long.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        match *self {
+            SectionHeader::Sh32(h) => as_bytes(h),
+            SectionHeader::Sh64(h) => as_bytes(h),
+        }
+    }
+
+    /// For `Rel` and `Rela` sections, the section which the relocations apply
+    /// to (as given by the `info` field).
+    pub fn relocated_section(&self, elf_file: &ElfFile<'a>) -> Option<SectionHeader<'a>> {
+        match self.get_type() {
+            Ok(ShType::Rel) | Ok(ShType::Rela) => {}
+            _ => return None,
+        }
+        section_at(elf_file, self.info())
+    }
+
+    /// The section referred to by the `link` field, e.g., the string table
+    /// for a symbol table. `None` if `link` is `SHN_UNDEF`.
+    pub fn linked_section(&self, elf_file: &ElfFile<'a>) -> Option<SectionHeader<'a>> {
+        section_at(elf_file, self.link())
+    }
+
+    /// For `Group` sections, the name of the signature symbol, which identifies
+    /// the group. The symbol is given by the `info` field, as an index into
+    /// the symbol table given by `link`.
+    pub fn group_signature(&self, elf_file: &ElfFile<'a>) -> Result<&'a str, &'static str> {
+        if self.get_type()? != ShType::Group {
+            return Err("Not a group section");
+        }
+        let symtab = self.linked_section(elf_file)
+                         .ok_or("Group section is not linked to a symbol table")?;
+        let index = self.info() as usize;
+        match symtab.get_data(elf_file)? {
+            SectionData::SymbolTable32(entries) => {
+                entries.get(index).ok_or("Group signature symbol out of range")
+                       .and_then(|entry| entry.get_name(elf_file))
+            }
+            SectionData::SymbolTable64(entries) => {
+                entries.get(index).ok_or("Group signature symbol out of range")
+                       .and_then(|entry| entry.get_name(elf_file))
+            }
+            _ => Err("Group section is not linked to a symbol table"),
+        }
+    }
+
+    /// The contents of the section, interpreted according to its type. The
+    /// data is checked to lie within the file, and to be long enough for any
+    /// header and a whole number of entries, so malformed input gives an error
+    /// rather than a panic.
     pub fn get_data(&self, elf_file: &ElfFile<'a>) -> Result<SectionData<'a>, &'static str> {
         macro_rules! array_data {
             ($data32: ident, $data64: ident) => {{
-                let data = self.raw_data(elf_file);
+                let data = self.try_raw_data(elf_file)?;
                 match elf_file.header.pt1.class() {
-                    Class::ThirtyTwo => SectionData::$data32(read_array(data)),
-                    Class::SixtyFour => SectionData::$data64(read_array(data)),
+                    Class::ThirtyTwo => SectionData::$data32(try_read_array(data)?),
+                    Class::SixtyFour => SectionData::$data64(try_read_array(data)?),
                     Class::None | Class::Other(_) => unreachable!(),
                 }
             }}
         }
 
-        self.get_type().map(|typ| match typ {
+        Ok(match self.get_type()? {
             ShType::Null | ShType::NoBits => SectionData::Empty,
             ShType::ProgBits |
             ShType::ShLib |
             ShType::OsSpecific(_) |
             ShType::ProcessorSpecific(_) |
-            ShType::User(_) => SectionData::Undefined(self.raw_data(elf_file)),
+            ShType::User(_) => SectionData::Undefined(self.try_raw_data(elf_file)?),
             ShType::SymTab => array_data!(SymbolTable32, SymbolTable64),
             ShType::DynSym => array_data!(DynSymbolTable32, DynSymbolTable64),
-            ShType::StrTab => SectionData::StrArray(self.raw_data(elf_file)),
+            ShType::StrTab => {
+                let data = self.try_raw_data(elf_file)?;
+                if data.last().is_some_and(|&b| b != 0) {
+                    return Err("String table is not null terminated");
+                }
+                SectionData::StrArray(data)
+            }
             ShType::InitArray | ShType::FiniArray | ShType::PreInitArray => {
                 array_data!(FnArray32, FnArray64)
             }
@@ -134,46 +259,79 @@ impl<'a> SectionHeader<'a> {
             ShType::Rel => array_data!(Rel32, Rel64),
             ShType::Dynamic => array_data!(Dynamic32, Dynamic64),
             ShType::Group => {
-                let data = self.raw_data(elf_file);
-                unsafe {
-                    let flags: &'a u32 = mem::transmute(&data[0]);
-                    let indicies: &'a [u32] = read_array(&data[4..]);
-                    SectionData::Group {
-                        flags: flags,
-                        indicies: indicies,
-                    }
+                let data = self.try_raw_data(elf_file)?;
+                if data.len() < 4 {
+                    return Err("Group section too short");
+                }
+                let indicies: &'a [u32] = try_read_array(&data[4..])?;
+                SectionData::Group {
+                    flags: &try_read_array(&data[..4])?[0],
+                    indicies,
                 }
             }
             ShType::SymTabShIndex => {
-                SectionData::SymTabShIndex(read_array(self.raw_data(elf_file)))
+                let data = self.try_raw_data(elf_file)?;
+                SectionData::SymTabShIndex(try_read_array(data)?)
             }
             ShType::Note => {
-                let data = self.raw_data(elf_file);
+                let data = self.try_raw_data(elf_file)?;
+                if data.len() < 12 {
+                    return Err("Note section too short");
+                }
+                let header: &'a NoteHeader = &try_read_array(&data[0..12])?[0];
+                let index = &data[12..];
                 match elf_file.header.pt1.class() {
-                    Class::ThirtyTwo => unimplemented!(),
-                    Class::SixtyFour => {
-                        let header: &'a NoteHeader = read(&data[0..12]);
-                        let index = &data[12..];
-                        SectionData::Note64(header, index)
-                    }
+                    Class::ThirtyTwo => SectionData::Note32(header, index),
+                    Class::SixtyFour => SectionData::Note64(header, index),
                     Class::None | Class::Other(_) => unreachable!(),
                 }
             }
             ShType::Hash => {
-                let data = self.raw_data(elf_file);
-                SectionData::HashTable(read(&data[0..12]))
+                let data = self.try_raw_data(elf_file)?;
+                if data.len() < 12 {
+                    return Err("Hash section too short");
+                }
+                let table: &'a HashTable = &try_read_array(&data[0..12])?[0];
+                // The buckets and chains follow the two counts.
+                let words = 2 + table.bucket_count() as u64 + table.chain_count() as u64;
+                if words * 4 > data.len() as u64 {
+                    return Err("Hash section too short");
+                }
+                SectionData::HashTable(table)
             }
         })
     }
 
+    /// The contents of the section in the file. This is empty for `NoBits`
+    /// sections (e.g., `.bss`), which take up no space in the file; their
+    /// `size` is only their size in memory.
     pub fn raw_data(&self, elf_file: &ElfFile<'a>) -> &'a [u8] {
-        assert_ne!(self.get_type().unwrap(), ShType::Null);
-        &elf_file.input[self.offset() as usize..(self.offset() + self.size()) as usize]
+        match self.get_type().unwrap() {
+            ShType::Null => panic!("Attempt to get data of null section"),
+            ShType::NoBits => &[],
+            _ => &elf_file.input[self.offset() as usize..(self.offset() + self.size()) as usize],
+        }
+    }
+
+    /// Like `raw_data`, but returns an error rather than panicking if the
+    /// section is null or its data lies outside the file.
+    pub fn try_raw_data(&self, elf_file: &ElfFile<'a>) -> Result<&'a [u8], &'static str> {
+        match self.get_type()? {
+            ShType::Null => return Err("Attempt to get data of null section"),
+            ShType::NoBits => return Ok(&[]),
+            _ => {}
+        }
+        let start = self.offset();
+        let end = start.checked_add(self.size()).ok_or("Section size overflows")?;
+        if end > elf_file.input.len() as u64 {
+            return Err("Section data out of range");
+        }
+        Ok(&elf_file.input[start as usize..end as usize])
     }
 
     #[cfg(feature = "compression")]
     pub fn decompressed_data(&self, elf_file: &ElfFile<'a>) -> Result<Cow<'a, [u8]>, &'static str> {
-        let raw = self.raw_data(elf_file);
+        let raw = self.try_raw_data(elf_file)?;
         Ok(if (self.flags() & SHF_COMPRESSED) == 0 {
             Cow::Borrowed(raw)
         } else {
@@ -209,6 +367,52 @@ impl<'a> SectionHeader<'a> {
         })
     }
 
+    pub fn get_flags(&self) -> SectionFlags {
+        SectionFlags(self.flags())
+    }
+
+    /// The number of entries in a table section (`size / entry_size`), or
+    /// `None` if the section does not have fixed-size entries.
+    pub fn entry_count(&self) -> Option<u64> {
+        match self.entry_size() {
+            0 => None,
+            entry_size => Some(self.size() / entry_size),
+        }
+    }
+
+    /// Check that a section whose type implies fixed-size entries (e.g.,
+    /// `SymTab` or `Rela`) has the entry size for that type in this file's
+    /// class. An entry size of zero means it is unspecified (as some linkers
+    /// leave it for `.init_array`), and is accepted.
+    pub fn validate_entry_size(&self, elf_file: &ElfFile<'a>) -> Result<(), &'static str> {
+        macro_rules! size {
+            ($t32: ty, $t64: ty) => {
+                match elf_file.header.pt1.class() {
+                    Class::ThirtyTwo => mem::size_of::<$t32>(),
+                    Class::SixtyFour => mem::size_of::<$t64>(),
+                    Class::None | Class::Other(_) => unreachable!(),
+                }
+            }
+        }
+
+        let expected = match self.get_type()? {
+            ShType::SymTab | ShType::DynSym => {
+                size!(symbol_table::Entry32, symbol_table::Entry64)
+            }
+            ShType::Rela => size!(Rela<P32>, Rela<P64>),
+            ShType::Rel => size!(Rel<P32>, Rel<P64>),
+            ShType::Dynamic => size!(Dynamic<P32>, Dynamic<P64>),
+            ShType::InitArray | ShType::FiniArray | ShType::PreInitArray => size!(P32, P64),
+            ShType::Hash if has_wide_hash_entries(elf_file) => size_of::<u64>(),
+            ShType::Hash | ShType::Group | ShType::SymTabShIndex => size_of::<u32>(),
+            _ => return Ok(()),
+        };
+        if self.entry_size() != 0 && self.entry_size() != expected as u64 {
+            return Err("Section entry size does not match its type");
+        }
+        Ok(())
+    }
+
     getter!(flags, u64);
     getter!(name, u32);
     getter!(address, u64);
@@ -221,6 +425,23 @@ impl<'a> SectionHeader<'a> {
     getter!(entry_size, u64);
 }
 
+// Whether `Hash` sections have 8-byte entries, as on 64-bit s390 and on Alpha
+// (EM_ALPHA), rather than the usual 4.
+fn has_wide_hash_entries(elf_file: &ElfFile) -> bool {
+    matches!((elf_file.header.pt2.machine().as_machine(), elf_file.header.pt1.class()),
+             (Machine::S390, Class::SixtyFour) | (Machine::Other(0x9026), _))
+}
+
+// Looks up a section index taken from a header field, rejecting SHN_UNDEF and
+// indices past the end of the section header table. A corrupt `e_shnum` may
+// let `index` into the reserved range, so this must not use `section_header`.
+fn section_at<'a>(elf_file: &ElfFile<'a>, index: u32) -> Option<SectionHeader<'a>> {
+    if index == SHN_UNDEF as u32 || index >= elf_file.header.pt2.sh_count() as u32 {
+        return None;
+    }
+    parse_section_header_at(elf_file.input, elf_file.header, index).ok()
+}
+
 impl<'a> fmt::Display for SectionHeader<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         macro_rules! sh_display {
@@ -338,8 +559,9 @@ pub enum SectionData<'a> {
     DynSymbolTable32(&'a [symbol_table::DynEntry32]),
     DynSymbolTable64(&'a [symbol_table::DynEntry64]),
     SymTabShIndex(&'a [u32]),
-    // Note32 uses 4-byte words, which I'm not sure how to manage.
+    // Both classes use 4-byte words for notes in practice (despite the spec).
     // The pointer is to the start of the name field in the note.
+    Note32(&'a NoteHeader, &'a [u8]),
     Note64(&'a NoteHeader, &'a [u8]),
     Rela32(&'a [Rela<P32>]),
     Rela64(&'a [Rela<P64>]),
@@ -348,6 +570,10 @@ pub enum SectionData<'a> {
     Dynamic32(&'a [Dynamic<P32>]),
     Dynamic64(&'a [Dynamic<P64>]),
     HashTable(&'a HashTable),
+    // The language-specific data areas (LSDAs) for exception handling, pointed
+    // to from FDEs in .eh_frame. Only returned by `ElfFile::gcc_except_table`,
+    // `get_data` does not look at section names.
+    GccExceptTable(&'a [u8]),
 }
 
 #[derive(Debug)]
@@ -365,13 +591,98 @@ impl<'a> Iterator for SectionStrings<'a> {
 }
 
 impl<'a> SectionData<'a> {
+    /// Iterate over the strings in a string table, up to the first empty
+    /// string. The null byte at the start of a string table (the empty string
+    /// at index 0) is skipped.
     pub fn strings(&self) -> Result<SectionStrings<'a>, ()> {
-        if let SectionData::StrArray(data) = *self {
-            Ok(SectionStrings { inner: read_strs_to_null(data) })
+        if let SectionData::StrArray(_) = *self {
+            self.as_strings().ok_or(())
         } else {
             Err(())
         }
     }
+
+    /// Like `strings`, but for any section whose data is plain bytes, not just
+    /// string tables, e.g., the compiler identification strings in `.comment`.
+    pub fn as_strings(&self) -> Option<SectionStrings<'a>> {
+        let data = match *self {
+            SectionData::StrArray(data) |
+            SectionData::Undefined(data) |
+            SectionData::GccExceptTable(data) => data,
+            _ => return None,
+        };
+        let data = if data.first() == Some(&0) { &data[1..] } else { data };
+        Some(SectionStrings { inner: read_strs_to_null(data) })
+    }
+
+    /// The entries of a symbol table section (`SymTab` or `DynSym`), whatever
+    /// the class.
+    pub fn symbols(&self) -> Option<SymbolSlice<'a>> {
+        match *self {
+            SectionData::SymbolTable32(entries) => Some(SymbolSlice::SymbolTable32(entries)),
+            SectionData::SymbolTable64(entries) => Some(SymbolSlice::SymbolTable64(entries)),
+            SectionData::DynSymbolTable32(entries) => Some(SymbolSlice::DynSymbolTable32(entries)),
+            SectionData::DynSymbolTable64(entries) => Some(SymbolSlice::DynSymbolTable64(entries)),
+            _ => None,
+        }
+    }
+
+    /// The function addresses in an `InitArray`, `FiniArray` or `PreInitArray`
+    /// section, widened to 64 bits, in the order in which they are stored.
+    pub fn function_addresses(&self) -> Option<impl Iterator<Item = u64> + 'a> {
+        let (addresses32, addresses64): (&'a [u32], &'a [u64]) = match *self {
+            SectionData::FnArray32(addresses) => (addresses, &[]),
+            SectionData::FnArray64(addresses) => (&[], addresses),
+            _ => return None,
+        };
+        Some(addresses32.iter().map(|&address| address as u64).chain(addresses64.iter().cloned()))
+    }
+
+    /// The entries of a relocation section (`Rel` or `Rela`), whatever the
+    /// class.
+    pub fn relocations(&self) -> Option<RelocationSlice<'a>> {
+        match *self {
+            SectionData::Rela32(entries) => Some(RelocationSlice::Rela32(entries)),
+            SectionData::Rela64(entries) => Some(RelocationSlice::Rela64(entries)),
+            SectionData::Rel32(entries) => Some(RelocationSlice::Rel32(entries)),
+            SectionData::Rel64(entries) => Some(RelocationSlice::Rel64(entries)),
+            _ => None,
+        }
+    }
+
+    /// Iterate over all the notes in a note section.
+    pub fn notes(&self) -> Option<NoteIter<'a>> {
+        match *self {
+            SectionData::Note32(header, data) |
+            SectionData::Note64(header, data) => Some(NoteIter::new(header, data)),
+            _ => None,
+        }
+    }
+
+    /// The member sections of a group section, in order. `None` if this is not
+    /// a group, or if any member index is not a valid section index.
+    pub fn group_members<'b>(&self,
+                             elf_file: &'b ElfFile<'a>)
+                             -> Option<impl Iterator<Item = SectionHeader<'a>> + 'b> {
+        let indices = match *self {
+            SectionData::Group { indicies, .. } => indicies,
+            _ => return None,
+        };
+        let count = elf_file.header.pt2.sh_count() as u32;
+        if indices.iter().any(|&index| index == SHN_UNDEF as u32 || index >= count) {
+            return None;
+        }
+        Some(indices.iter().filter_map(move |&index| section_at(elf_file, index)))
+    }
+
+    /// Whether this is a `GRP_COMDAT` group, i.e., one which the linker keeps
+    /// only one copy of among groups with the same signature.
+    pub fn is_comdat(&self) -> bool {
+        match *self {
+            SectionData::Group { flags, .. } => *flags as u64 & GRP_COMDAT != 0,
+            _ => false,
+        }
+    }
 }
 
 // Distinguished ShType values.
@@ -397,6 +708,70 @@ pub const SHF_COMPRESSED: u64 = 0x800;
 pub const SHF_MASKOS: u64 = 0x0ff00000;
 pub const SHF_MASKPROC: u64 = 0xf0000000;
 
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct SectionFlags(pub u64);
+
+impl SectionFlags {
+    pub fn is_writable(&self) -> bool {
+        self.0 & SHF_WRITE == SHF_WRITE
+    }
+
+    pub fn is_alloc(&self) -> bool {
+        self.0 & SHF_ALLOC == SHF_ALLOC
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.0 & SHF_EXECINSTR == SHF_EXECINSTR
+    }
+
+    pub fn is_merge(&self) -> bool {
+        self.0 & SHF_MERGE == SHF_MERGE
+    }
+
+    pub fn is_strings(&self) -> bool {
+        self.0 & SHF_STRINGS == SHF_STRINGS
+    }
+
+    pub fn is_tls(&self) -> bool {
+        self.0 & SHF_TLS == SHF_TLS
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.0 & SHF_COMPRESSED == SHF_COMPRESSED
+    }
+}
+
+impl fmt::Debug for SectionFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const NAMES: [(u64, &str); 11] = [(SHF_WRITE, "SHF_WRITE"),
+                                         (SHF_ALLOC, "SHF_ALLOC"),
+                                         (SHF_EXECINSTR, "SHF_EXECINSTR"),
+                                         (SHF_MERGE, "SHF_MERGE"),
+                                         (SHF_STRINGS, "SHF_STRINGS"),
+                                         (SHF_INFO_LINK, "SHF_INFO_LINK"),
+                                         (SHF_LINK_ORDER, "SHF_LINK_ORDER"),
+                                         (SHF_OS_NONCONFORMING, "SHF_OS_NONCONFORMING"),
+                                         (SHF_GROUP, "SHF_GROUP"),
+                                         (SHF_TLS, "SHF_TLS"),
+                                         (SHF_COMPRESSED, "SHF_COMPRESSED")];
+
+        write!(f, "SectionFlags(")?;
+        let mut rest = self.0;
+        let mut first = true;
+        for &(flag, name) in NAMES.iter() {
+            if rest & flag == flag {
+                write!(f, "{}{}", if first { "" } else { " | " }, name)?;
+                rest &= !flag;
+                first = false;
+            }
+        }
+        if rest != 0 || first {
+            write!(f, "{}{:#x}", if first { "" } else { " | " }, rest)?;
+        }
+        write!(f, ")")
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 #[repr(C)]
 pub struct CompressionHeader64 {
@@ -452,11 +827,211 @@ pub const COMPRESS_HIOS: u32 = 0x6fffffff;
 pub const COMPRESS_LOPROC: u32 = 0x70000000;
 pub const COMPRESS_HIPROC: u32 = 0x7fffffff;
 
+/// A DWARF debug information section, e.g., `.debug_info`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DwarfSection {
+    Abbrev,
+    Addr,
+    Aranges,
+    Frame,
+    Info,
+    Line,
+    LineStr,
+    Loc,
+    LocLists,
+    MacInfo,
+    Macro,
+    PubNames,
+    PubTypes,
+    Ranges,
+    RngLists,
+    Str,
+    StrOffsets,
+    Types,
+}
+
+impl DwarfSection {
+    /// Recognise a section name, either `.debug_*` or the older compressed
+    /// form `.zdebug_*`. Unlike sections with `SHF_COMPRESSED` set, `.zdebug_*`
+    /// sections start with "ZLIB" and a big-endian size rather than a
+    /// compression header, so `SectionHeader::decompressed_data` does not
+    /// decompress them.
+    pub fn from_name(name: &str) -> Option<DwarfSection> {
+        let suffix = name.strip_prefix(".debug_").or_else(|| name.strip_prefix(".zdebug_"))?;
+        Some(match suffix {
+            "abbrev" => DwarfSection::Abbrev,
+            "addr" => DwarfSection::Addr,
+            "aranges" => DwarfSection::Aranges,
+            "frame" => DwarfSection::Frame,
+            "info" => DwarfSection::Info,
+            "line" => DwarfSection::Line,
+            "line_str" => DwarfSection::LineStr,
+            "loc" => DwarfSection::Loc,
+            "loclists" => DwarfSection::LocLists,
+            "macinfo" => DwarfSection::MacInfo,
+            "macro" => DwarfSection::Macro,
+            "pubnames" => DwarfSection::PubNames,
+            "pubtypes" => DwarfSection::PubTypes,
+            "ranges" => DwarfSection::Ranges,
+            "rnglists" => DwarfSection::RngLists,
+            "str" => DwarfSection::Str,
+            "str_offsets" => DwarfSection::StrOffsets,
+            "types" => DwarfSection::Types,
+            _ => return None,
+        })
+    }
+
+    /// The canonical (uncompressed) name of the section.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            DwarfSection::Abbrev => ".debug_abbrev",
+            DwarfSection::Addr => ".debug_addr",
+            DwarfSection::Aranges => ".debug_aranges",
+            DwarfSection::Frame => ".debug_frame",
+            DwarfSection::Info => ".debug_info",
+            DwarfSection::Line => ".debug_line",
+            DwarfSection::LineStr => ".debug_line_str",
+            DwarfSection::Loc => ".debug_loc",
+            DwarfSection::LocLists => ".debug_loclists",
+            DwarfSection::MacInfo => ".debug_macinfo",
+            DwarfSection::Macro => ".debug_macro",
+            DwarfSection::PubNames => ".debug_pubnames",
+            DwarfSection::PubTypes => ".debug_pubtypes",
+            DwarfSection::Ranges => ".debug_ranges",
+            DwarfSection::RngLists => ".debug_rnglists",
+            DwarfSection::Str => ".debug_str",
+            DwarfSection::StrOffsets => ".debug_str_offsets",
+            DwarfSection::Types => ".debug_types",
+        }
+    }
+}
+
 // Group flags
 pub const GRP_COMDAT: u64 = 0x1;
 pub const GRP_MASKOS: u64 = 0x0ff00000;
 pub const GRP_MASKPROC: u64 = 0xf0000000;
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GnuNoteType {
+    AbiTag,
+    HwCap,
+    BuildId,
+    GoldVersion,
+    Property,
+    Other(u32),
+}
+
+// Types of notes owned by "GNU".
+pub const NT_GNU_ABI_TAG: u32 = 1;
+pub const NT_GNU_HWCAP: u32 = 2;
+pub const NT_GNU_BUILD_ID: u32 = 3;
+pub const NT_GNU_GOLD_VERSION: u32 = 4;
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+
+/// The type of the JSON packaging metadata note (owner "FDO") in
+/// `.note.package`.
+pub const NT_FDO_PACKAGING_METADATA: u32 = 0xcafe1a7e;
+
+/// The contents of an `NT_GNU_ABI_TAG` note (in `.note.ABI-tag`): the OS the
+/// binary is for and the minimum kernel version it needs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AbiTag {
+    /// One of the `ELF_NOTE_OS_*` constants.
+    pub os: u32,
+    pub major: u32,
+    pub minor: u32,
+    pub subminor: u32,
+}
+
+impl AbiTag {
+    /// Parse the descriptor of an `NT_GNU_ABI_TAG` note, which is four words.
+    pub fn parse(desc: &[u8]) -> Result<AbiTag, &'static str> {
+        if desc.len() < 16 {
+            return Err("ABI tag note too short");
+        }
+        let words: &[u32] = try_read_array(&desc[..16])?;
+        Ok(AbiTag {
+            os: words[0],
+            major: words[1],
+            minor: words[2],
+            subminor: words[3],
+        })
+    }
+}
+
+// Values of `AbiTag::os`.
+pub const ELF_NOTE_OS_LINUX: u32 = 0;
+pub const ELF_NOTE_OS_GNU: u32 = 1;
+pub const ELF_NOTE_OS_SOLARIS2: u32 = 2;
+pub const ELF_NOTE_OS_FREEBSD: u32 = 3;
+
+/// A property in the descriptor of an `NT_GNU_PROPERTY_TYPE_0` note (in
+/// `.note.gnu.property`), which records features the binary was built with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GnuProperty<'a> {
+    /// `pr_type`, e.g., `GNU_PROPERTY_X86_FEATURE_1_AND`.
+    pub type_: u32,
+    pub data: &'a [u8],
+}
+
+impl<'a> GnuProperty<'a> {
+    /// The feature bits of an x86 or AArch64 `FEATURE_1_AND` property, which
+    /// are set only if every object linked into the binary has them.
+    pub fn feature_1_and(&self) -> Option<u32> {
+        match self.type_ {
+            GNU_PROPERTY_X86_FEATURE_1_AND | GNU_PROPERTY_AARCH64_FEATURE_1_AND => {
+                let words = self.data.get(..4).and_then(|data| try_read_array(data).ok());
+                words.map(|words: &[u32]| words[0])
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Iterate over the properties in the descriptor of an `NT_GNU_PROPERTY_TYPE_0`
+/// note. Each property is padded to `pointer_size`; iteration stops at the first
+/// truncated or misaligned one. There are none unless `pointer_size` is 4 or 8.
+pub fn gnu_properties<'a>(mut desc: &'a [u8],
+                          pointer_size: usize)
+                          -> impl Iterator<Item = GnuProperty<'a>> + 'a {
+    if pointer_size != 4 && pointer_size != 8 {
+        desc = &[];
+    }
+    ::core::iter::from_fn(move || {
+        let words: &[u32] = try_read_array(desc.get(..8)?).ok()?;
+        let (type_, size) = (words[0], words[1]);
+        let end = (size as usize).checked_add(8)?;
+        let data = desc.get(8..end)?;
+        let next = end.checked_add(pointer_size - 1)? & !(pointer_size - 1);
+        desc = desc.get(next..).unwrap_or(&[]);
+        Some(GnuProperty {
+            type_,
+            data,
+        })
+    })
+}
+
+/// The control-flow protection features from the `FEATURE_1_AND` properties.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct GnuFeatures {
+    /// x86 indirect branch tracking (CET).
+    pub ibt: bool,
+    /// x86 shadow stack (CET).
+    pub shstk: bool,
+    /// AArch64 branch target identification.
+    pub bti: bool,
+    /// AArch64 pointer authentication.
+    pub pac: bool,
+}
+
+// Values of `GnuProperty::type_`, and their feature bits.
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc0000000;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_BTI: u32 = 0x1;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_PAC: u32 = 0x2;
+pub const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc0000002;
+pub const GNU_PROPERTY_X86_FEATURE_1_IBT: u32 = 0x1;
+pub const GNU_PROPERTY_X86_FEATURE_1_SHSTK: u32 = 0x2;
+
 #[derive(Debug)]
 #[repr(C)]
 pub struct Rela<P> {
@@ -475,6 +1050,110 @@ pub struct Rel<P> {
 unsafe impl<P> Pod for Rela<P> {}
 unsafe impl<P> Pod for Rel<P> {}
 
+/// A `Rel` or `Rela` entry of either class, widened to 64 bits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Relocation {
+    pub offset: u64,
+    pub symbol_table_index: u32,
+    pub type_: u32,
+    /// The explicit addend of a `Rela` entry, sign-extended for 32-bit files.
+    /// `None` for `Rel` entries, whose addend is stored at the location being
+    /// relocated (see `Rel::get_implicit_addend`).
+    pub addend: Option<u64>,
+}
+
+/// The entries of a relocation table (`Rel` or `Rela`) of either class, seen as
+/// `Relocation`s so that code processing them does not need to care about the
+/// width.
+#[derive(Clone, Copy, Debug)]
+pub enum RelocationSlice<'a> {
+    Rela32(&'a [Rela<P32>]),
+    Rela64(&'a [Rela<P64>]),
+    Rel32(&'a [Rel<P32>]),
+    Rel64(&'a [Rel<P64>]),
+}
+
+impl<'a> RelocationSlice<'a> {
+    pub fn len(&self) -> usize {
+        match *self {
+            RelocationSlice::Rela32(entries) => entries.len(),
+            RelocationSlice::Rela64(entries) => entries.len(),
+            RelocationSlice::Rel32(entries) => entries.len(),
+            RelocationSlice::Rel64(entries) => entries.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<Relocation> {
+        match *self {
+            RelocationSlice::Rela32(entries) => {
+                entries.get(index).map(|e| {
+                    Relocation {
+                        offset: e.get_offset() as u64,
+                        symbol_table_index: e.get_symbol_table_index(),
+                        type_: e.get_type() as u32,
+                        addend: Some(e.get_addend() as i32 as i64 as u64),
+                    }
+                })
+            }
+            RelocationSlice::Rela64(entries) => {
+                entries.get(index).map(|e| {
+                    Relocation {
+                        offset: e.get_offset(),
+                        symbol_table_index: e.get_symbol_table_index(),
+                        type_: e.get_type(),
+                        addend: Some(e.get_addend()),
+                    }
+                })
+            }
+            RelocationSlice::Rel32(entries) => {
+                entries.get(index).map(|e| {
+                    Relocation {
+                        offset: e.get_offset() as u64,
+                        symbol_table_index: e.get_symbol_table_index(),
+                        type_: e.get_type() as u32,
+                        addend: None,
+                    }
+                })
+            }
+            RelocationSlice::Rel64(entries) => {
+                entries.get(index).map(|e| {
+                    Relocation {
+                        offset: e.get_offset(),
+                        symbol_table_index: e.get_symbol_table_index(),
+                        type_: e.get_type(),
+                        addend: None,
+                    }
+                })
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Relocation> + 'a {
+        let slice = *self;
+        (0..self.len()).filter_map(move |i| slice.get(i))
+    }
+}
+
+// IRELATIVE relocation types, whose addend is the address of an ifunc resolver.
+pub const R_386_IRELATIVE: u32 = 42;
+pub const R_X86_64_IRELATIVE: u32 = 37;
+pub const R_ARM_IRELATIVE: u32 = 160;
+pub const R_AARCH64_IRELATIVE: u32 = 1032;
+
+fn irelative_type(machine: Machine) -> Option<u32> {
+    match machine {
+        Machine::X86 => Some(R_386_IRELATIVE),
+        Machine::X86_64 => Some(R_X86_64_IRELATIVE),
+        Machine::Arm => Some(R_ARM_IRELATIVE),
+        Machine::AArch64 => Some(R_AARCH64_IRELATIVE),
+        _ => None,
+    }
+}
+
 impl Rela<P32> {
     pub fn get_offset(&self) -> u32 {
         self.offset
@@ -488,6 +1167,15 @@ impl Rela<P32> {
     pub fn get_type(&self) -> u8 {
         self.info as u8
     }
+    /// The address of the ifunc resolver if this is an IRELATIVE relocation
+    /// for `machine`.
+    pub fn get_ifunc_resolver(&self, machine: Machine) -> Option<u32> {
+        if irelative_type(machine) == Some(self.get_type() as u32) {
+            Some(self.addend)
+        } else {
+            None
+        }
+    }
 }
 impl Rela<P64> {
     pub fn get_offset(&self) -> u64 {
@@ -502,6 +1190,15 @@ impl Rela<P64> {
     pub fn get_type(&self) -> u32 {
         (self.info & 0xffffffff) as u32
     }
+    /// The address of the ifunc resolver if this is an IRELATIVE relocation
+    /// for `machine`.
+    pub fn get_ifunc_resolver(&self, machine: Machine) -> Option<u64> {
+        if irelative_type(machine) == Some(self.get_type()) {
+            Some(self.addend)
+        } else {
+            None
+        }
+    }
 }
 impl Rel<P32> {
     pub fn get_offset(&self) -> u32 {
@@ -513,6 +1210,16 @@ impl Rel<P32> {
     pub fn get_type(&self) -> u8 {
         self.info as u8
     }
+    /// The addend of a dynamic relocation, which `Rel` entries store at the
+    /// location being relocated, in the file's byte order.
+    pub fn get_implicit_addend(&self, elf_file: &ElfFile) -> Result<u32, &'static str> {
+        let mut addend = [0; 4];
+        addend.copy_from_slice(relocation_target(elf_file, self.offset as u64, 4)?);
+        Ok(match elf_file.header.pt1.data() {
+            Data::BigEndian => u32::from_be_bytes(addend),
+            _ => u32::from_le_bytes(addend),
+        })
+    }
 }
 impl Rel<P64> {
     pub fn get_offset(&self) -> u64 {
@@ -524,6 +1231,51 @@ impl Rel<P64> {
     pub fn get_type(&self) -> u32 {
         (self.info & 0xffffffff) as u32
     }
+    /// The addend of a dynamic relocation, which `Rel` entries store at the
+    /// location being relocated, in the file's byte order.
+    pub fn get_implicit_addend(&self, elf_file: &ElfFile) -> Result<u64, &'static str> {
+        let mut addend = [0; 8];
+        addend.copy_from_slice(relocation_target(elf_file, self.offset, 8)?);
+        Ok(match elf_file.header.pt1.data() {
+            Data::BigEndian => u64::from_be_bytes(addend),
+            _ => u64::from_le_bytes(addend),
+        })
+    }
+}
+
+macro_rules! reloc_display {
+    ($rel: ty $(, $addend: ident)*) => {
+        impl fmt::Display for $rel {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                writeln!(f, "Relocation:")?;
+                writeln!(f, "    offset:           {:#x}", self.get_offset())?;
+                writeln!(f, "    symbol index:     {}", self.get_symbol_table_index())?;
+                writeln!(f, "    type:             {}", self.get_type())?;
+                $(writeln!(f, "    addend:           {:#x}", self.$addend())?;)*
+                Ok(())
+            }
+        }
+    }
+}
+
+reloc_display!(Rela<P32>, get_addend);
+reloc_display!(Rela<P64>, get_addend);
+reloc_display!(Rel<P32>);
+reloc_display!(Rel<P64>);
+
+// The bytes at the virtual address `addr` which a dynamic relocation applies to.
+fn relocation_target<'a>(elf_file: &ElfFile<'a>,
+                         addr: u64,
+                         size: usize)
+                         -> Result<&'a [u8], &'static str> {
+    let start = elf_file.virtual_addr_to_offset(addr)
+        .ok_or("Relocation target is not in a Load segment")?;
+    match start.checked_add(size as u64) {
+        Some(end) if end <= elf_file.input.len() as u64 => {
+            Ok(&elf_file.input[start as usize..end as usize])
+        }
+        _ => Err("Relocation target out of range"),
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -541,11 +1293,43 @@ impl NoteHeader {
         self.type_
     }
 
+    /// The note type, decoded as one of the types used in notes owned by
+    /// "GNU". Types are only meaningful together with the owner (the note's
+    /// name): e.g., type 1 is `NT_PRSTATUS` in a "CORE" note.
+    pub fn get_gnu_type(&self) -> GnuNoteType {
+        match self.type_ {
+            NT_GNU_ABI_TAG => GnuNoteType::AbiTag,
+            NT_GNU_HWCAP => GnuNoteType::HwCap,
+            NT_GNU_BUILD_ID => GnuNoteType::BuildId,
+            NT_GNU_GOLD_VERSION => GnuNoteType::GoldVersion,
+            NT_GNU_PROPERTY_TYPE_0 => GnuNoteType::Property,
+            other => GnuNoteType::Other(other),
+        }
+    }
+
     pub fn name<'a>(&'a self, input: &'a [u8]) -> &'a str {
-        let result = read_str(input).expect("failed reading input str");
+        self.try_name(input).expect("failed reading input str")
+    }
+
+    /// Like `name`, but returns an error if the name is not exactly
+    /// `name_size` bytes including a null terminator.
+    pub fn try_name<'a>(&'a self, input: &'a [u8]) -> Result<&'a str, &'static str> {
+        let size = self.name_size as usize;
+        if size == 0 {
+            return Ok("");
+        }
+        if size > input.len() {
+            return Err("Note name out of range");
+        }
+        if input[size - 1] != 0 {
+            return Err("Note name is not null terminated");
+        }
+        let result = read_str(&input[..size])?;
         // - 1 is due to null terminator
-        assert_eq!(result.len(), (self.name_size - 1) as usize);
-        result
+        if result.len() != size - 1 {
+            return Err("Note name contains a null byte");
+        }
+        Ok(result)
     }
 
     pub fn desc<'a>(&'a self, input: &'a [u8]) -> &'a [u8] {
@@ -556,12 +1340,84 @@ impl NoteHeader {
             slice::from_raw_parts(ptr, self.desc_size as usize)
         }
     }
+
+    /// The descriptor interpreted as an array of `T`s, e.g., the words of an
+    /// ABI tag note.
+    pub fn desc_as<'a, T: Pod>(&'a self, input: &'a [u8]) -> Result<&'a [T], &'static str> {
+        let offset = (self.name_size as usize + 3) & !0x3;
+        if offset + self.desc_size as usize > input.len() {
+            return Err("Note descriptor out of range");
+        }
+        let desc = self.desc(input);
+        let size = size_of::<T>();
+        if size == 0 || !desc.len().is_multiple_of(size) {
+            return Err("Note descriptor is not a whole number of elements");
+        }
+        if !(desc.as_ptr() as usize).is_multiple_of(align_of::<T>()) {
+            return Err("Note descriptor is misaligned");
+        }
+        Ok(read_array(desc))
+    }
+}
+
+/// Iterates over consecutive notes, yielding the name, type, and descriptor of
+/// each. Iteration stops at the first note which does not fit in the input.
+/// Notes with a malformed name (see `NoteHeader::try_name`) are skipped.
+#[derive(Clone, Debug)]
+pub struct NoteIter<'a> {
+    next: Option<(&'a NoteHeader, &'a [u8])>,
+}
+
+impl<'a> NoteIter<'a> {
+    /// `input` is the data following `header`, as found in `SectionData::Note64`
+    /// and friends.
+    pub fn new(header: &'a NoteHeader, input: &'a [u8]) -> NoteIter<'a> {
+        NoteIter { next: Some((header, input)) }
+    }
 }
 
-pub fn sanity_check<'a>(header: SectionHeader<'a>, _file: &ElfFile<'a>) -> Result<(), &'static str> {
-    if try!(header.get_type()) == ShType::Null {
-        return Ok(());
+impl<'a> Iterator for NoteIter<'a> {
+    type Item = (&'a str, u32, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (header, input) = self.next.take()?;
+
+            // The name and descriptor are each padded to the next u32.
+            let desc_start = (header.name_size as usize + 3) & !0x3;
+            let desc_end = desc_start + header.desc_size as usize;
+            if desc_end > input.len() {
+                return None;
+            }
+
+            let next_start = (desc_end + 3) & !0x3;
+            if next_start + size_of::<NoteHeader>() <= input.len() {
+                let rest = &input[next_start..];
+                self.next = Some((read(&rest[..size_of::<NoteHeader>()]),
+                                  &rest[size_of::<NoteHeader>()..]));
+            }
+
+            if let Ok(name) = header.try_name(input) {
+                return Some((name, header.type_(), &input[desc_start..desc_end]));
+            }
+        }
+    }
+}
+
+fn as_bytes<T: Pod>(value: &T) -> &[u8] {
+    unsafe { slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) }
+}
+
+pub fn sanity_check<'a>(header: SectionHeader<'a>, file: &ElfFile<'a>) -> Result<(), &'static str> {
+    match header.get_type()? {
+        ShType::Null => return Ok(()),
+        ShType::StrTab => {
+            let data = header.try_raw_data(file)?;
+            check!(data.last().is_none_or(|&b| b == 0), "String table is not null terminated");
+        }
+        _ => {}
     }
+    header.validate_entry_size(file)?;
     // TODO
     Ok(())
 }
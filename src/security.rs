@@ -0,0 +1,99 @@
+use ElfFile;
+use header;
+use dynamic;
+
+/// A summary of the hardening features of a binary, in the style of
+/// `checksec`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SecurityReport {
+    /// A position independent executable, i.e., the executable itself is
+    /// loaded at a random address.
+    pub pie: bool,
+    pub relro: Relro,
+    /// All symbols are bound at load time (`-z now`).
+    pub bind_now: bool,
+    /// There is a `PT_GNU_STACK` segment which is not executable.
+    pub nx_stack: bool,
+    /// Some `_FORTIFY_SOURCE` functions (e.g., `__printf_chk`) are used.
+    pub fortify: bool,
+    /// `__stack_chk_fail` or `__stack_chk_guard` is used.
+    pub stack_protector: bool,
+    /// The GNU property note marks the binary as compatible with control-flow
+    /// protection: both IBT and SHSTK on x86, or BTI on AArch64.
+    pub cf_protection: bool,
+    pub rpath: bool,
+    pub runpath: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Relro {
+    None,
+    /// Some data is made read-only after relocation, but not the GOT entries
+    /// used by the PLT, since those are bound lazily.
+    Partial,
+    Full,
+}
+
+impl SecurityReport {
+    pub fn new(elf_file: &ElfFile) -> SecurityReport {
+        let dynamic_value = |tag| elf_file.get_dynamic_value(tag);
+        let flags = dynamic_value(dynamic::Tag::Flags).unwrap_or(0);
+        let flags_1 = dynamic_value(dynamic::Tag::Flags1).unwrap_or(0);
+        let bind_now = dynamic_value(dynamic::Tag::BindNow).is_some() ||
+                       flags & dynamic::FLAG_BIND_NOW != 0 ||
+                       flags_1 & dynamic::FLAG_1_NOW != 0;
+
+        // Older linkers don't set DF_1_PIE, but shared libraries rarely have an
+        // interpreter.
+        let pie = elf_file.is_pie() ||
+                  elf_file.header.pt2.get_type() == header::Type::SharedObject &&
+                  elf_file.interpreter().is_some();
+
+        let relro = match (elf_file.has_relro(), bind_now) {
+            (false, _) => Relro::None,
+            (true, false) => Relro::Partial,
+            (true, true) => Relro::Full,
+        };
+        let nx_stack = elf_file.has_executable_stack() == Some(false);
+
+        let mut fortify = false;
+        let mut stack_protector = false;
+        for_each_symbol_name(elf_file, |name| {
+            fortify |= name.starts_with("__") && name.ends_with("_chk");
+            stack_protector |= name == "__stack_chk_fail" || name == "__stack_chk_guard";
+        });
+
+        SecurityReport {
+            pie,
+            relro,
+            bind_now,
+            nx_stack,
+            fortify,
+            stack_protector,
+            cf_protection: has_cf_protection(elf_file),
+            rpath: dynamic_value(dynamic::Tag::RPath).is_some(),
+            runpath: dynamic_value(dynamic::Tag::RunPath).is_some(),
+        }
+    }
+}
+
+// Calls `f` with the name of every symbol in the symbol tables, both static and
+// dynamic.
+fn for_each_symbol_name<'a, F: FnMut(&'a str)>(elf_file: &ElfFile<'a>, mut f: F) {
+    for sect in elf_file.section_iter() {
+        let symbols = match sect.get_data(elf_file).ok().and_then(|data| data.symbols()) {
+            Some(symbols) => symbols,
+            None => continue,
+        };
+        for entry in symbols.iter() {
+            if let Ok(name) = entry.get_name(elf_file) {
+                f(name);
+            }
+        }
+    }
+}
+
+fn has_cf_protection(elf_file: &ElfFile) -> bool {
+    let features = elf_file.gnu_features();
+    features.ibt && features.shstk || features.bti
+}
@@ -3,6 +3,9 @@ use sections;
 
 use zero::Pod;
 
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
 use core::fmt;
 use core::mem;
 
@@ -75,6 +78,31 @@ pub trait Entry {
         Type_(self.info() & 0xf).as_type()
     }
 
+    /// Whether this symbol is undefined (`SHN_UNDEF`), i.e., refers to a
+    /// definition in another object.
+    fn is_undefined(&self) -> bool {
+        self.shndx() == sections::SHN_UNDEF
+    }
+
+    /// Whether this symbol is defined, either in a section of this file or as
+    /// an absolute or common symbol.
+    fn is_defined(&self) -> bool {
+        !self.is_undefined()
+    }
+
+    /// Where this symbol is defined, as given by `st_shndx`. Unlike using
+    /// `shndx` as a section index, this distinguishes the reserved indices.
+    fn section(&self) -> SymbolSection {
+        match self.shndx() {
+            sections::SHN_UNDEF => SymbolSection::Undefined,
+            sections::SHN_ABS => SymbolSection::Absolute,
+            sections::SHN_COMMON => SymbolSection::Common,
+            sections::SHN_XINDEX => SymbolSection::Extended,
+            index if index >= sections::SHN_LORESERVE => SymbolSection::Reserved(index),
+            index => SymbolSection::Defined(index),
+        }
+    }
+
     fn get_section_header<'a>(&'a self,
                               elf_file: &ElfFile<'a>,
                               self_index: usize)
@@ -105,6 +133,69 @@ pub trait Entry {
             i => elf_file.section_header(i),
         }
     }
+
+    /// The distance of this symbol from the file's entry point (`st_value -
+    /// e_entry`), or `None` if it is not a defined function.
+    fn offset_from_entry(&self, elf_file: &ElfFile) -> Option<i64> {
+        if self.get_type() != Ok(Type::Func) || self.shndx() == sections::SHN_UNDEF {
+            return None;
+        }
+        Some(self.value().wrapping_sub(elf_file.header.pt2.entry_point()) as i64)
+    }
+
+    /// The index of the section this symbol is defined in, or the reserved
+    /// index (e.g., `SHN_ABS`) for symbols without one. `SHN_XINDEX` is
+    /// resolved through the `SymTabShIndex` section linked to `symtab_section`,
+    /// which must be the symbol table containing this entry.
+    fn get_section_index<'a>(&'a self,
+                             elf_file: &ElfFile<'a>,
+                             symtab_section: sections::SectionHeader<'a>)
+                             -> Option<u32>
+        where Self: Sized
+    {
+        if self.shndx() != sections::SHN_XINDEX {
+            return Some(self.shndx() as u32);
+        }
+
+        let index = index_in_table(self, symtab_section.try_raw_data(elf_file).ok()?)?;
+        let symtab_index = elf_file.section_index(&symtab_section)? as u32;
+        let shndx_section = elf_file.section_iter().find(|sect| {
+            sect.get_type() == Ok(sections::ShType::SymTabShIndex) && sect.link() == symtab_index
+        });
+        match shndx_section.map(|sect| sect.get_data(elf_file)) {
+            Some(Ok(sections::SectionData::SymTabShIndex(indices))) => indices.get(index).cloned(),
+            _ => None,
+        }
+    }
+}
+
+/// The section a symbol is defined in, see `Entry::section`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SymbolSection {
+    /// Defined in another object (`SHN_UNDEF`).
+    Undefined,
+    /// Defined in the section with this index.
+    Defined(u16),
+    /// An absolute value, not relative to any section (`SHN_ABS`).
+    Absolute,
+    /// An uninitialised common block, allocated by the linker (`SHN_COMMON`).
+    Common,
+    /// The index does not fit in `st_shndx` (`SHN_XINDEX`); it is in the
+    /// `SymTabShIndex` section, see `get_section_index`.
+    Extended,
+    /// Another reserved index, e.g., a processor-specific one such as
+    /// `SHN_X86_64_LCOMMON`.
+    Reserved(u16),
+}
+
+// The index of `entry` within `table`, which must be the raw data of the symbol
+// table it was read from.
+pub(crate) fn index_in_table<E>(entry: &E, table: &[u8]) -> Option<usize> {
+    let offset = (entry as *const E as usize).wrapping_sub(table.as_ptr() as usize);
+    if offset >= table.len() || !offset.is_multiple_of(size_of::<E>()) {
+        return None;
+    }
+    Some(offset / size_of::<E>())
 }
 
 impl fmt::Display for Entry {
@@ -124,7 +215,13 @@ impl fmt::Display for Entry {
 macro_rules! impl_entry {
     ($name: ident with ElfFile::$strfunc: ident) => {
         impl Entry for $name {
+            // Section symbols are named after the section they refer to.
             fn get_name<'a>(&'a self, elf_file: &ElfFile<'a>) -> Result<&'a str, &'static str> {
+                let shndx = self.shndx();
+                if self.get_type() == Ok(Type::Section) && shndx != sections::SHN_UNDEF &&
+                   shndx < sections::SHN_LORESERVE {
+                    return elf_file.section_header(shndx).and_then(|h| h.get_name(elf_file));
+                }
                 elf_file.$strfunc(self.name())
             }
 
@@ -142,6 +239,99 @@ impl_entry!(Entry64 with ElfFile::get_string);
 impl_entry!(DynEntry32 with ElfFile::get_dyn_string);
 impl_entry!(DynEntry64 with ElfFile::get_dyn_string);
 
+/// The entries of a symbol table, static or dynamic, of either class. Each
+/// entry is seen through the `Entry` trait, so that code processing symbols
+/// does not need to care about the width.
+#[derive(Clone, Copy, Debug)]
+pub enum SymbolSlice<'a> {
+    SymbolTable32(&'a [Entry32]),
+    SymbolTable64(&'a [Entry64]),
+    DynSymbolTable32(&'a [DynEntry32]),
+    DynSymbolTable64(&'a [DynEntry64]),
+}
+
+impl<'a> SymbolSlice<'a> {
+    pub fn len(&self) -> usize {
+        match *self {
+            SymbolSlice::SymbolTable32(entries) => entries.len(),
+            SymbolSlice::SymbolTable64(entries) => entries.len(),
+            SymbolSlice::DynSymbolTable32(entries) => entries.len(),
+            SymbolSlice::DynSymbolTable64(entries) => entries.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&'a dyn Entry> {
+        match *self {
+            SymbolSlice::SymbolTable32(entries) => entries.get(index).map(|e| e as &dyn Entry),
+            SymbolSlice::SymbolTable64(entries) => entries.get(index).map(|e| e as &dyn Entry),
+            SymbolSlice::DynSymbolTable32(entries) => entries.get(index).map(|e| e as &dyn Entry),
+            SymbolSlice::DynSymbolTable64(entries) => entries.get(index).map(|e| e as &dyn Entry),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a dyn Entry> + 'a {
+        let slice = *self;
+        (0..self.len()).filter_map(move |i| slice.get(i))
+    }
+}
+
+/// The defined function symbols of the symbol table (or the dynamic symbol
+/// table, if there is none), sorted by address so that many addresses can be
+/// looked up quickly. Building it allocates and sorts the whole table, so it is
+/// only worth it over `ElfFile::symbol_for_address` (a linear scan) for more
+/// than a few lookups.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct SymbolAddressIndex<'a> {
+    symbols: Option<SymbolSlice<'a>>,
+    // The address and index in `symbols` of each function symbol.
+    addresses: Vec<(u64, usize)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> SymbolAddressIndex<'a> {
+    pub fn new(elf_file: &ElfFile<'a>) -> SymbolAddressIndex<'a> {
+        let symbols = elf_file.symbol_table_or_dynsym()
+                              .and_then(|header| header.get_data(elf_file).ok())
+                              .and_then(|data| data.symbols());
+        let mut addresses = Vec::new();
+        for (index, entry) in symbols.iter().flat_map(|symbols| symbols.iter()).enumerate() {
+            if entry.get_type() == Ok(Type::Func) && entry.shndx() != sections::SHN_UNDEF {
+                addresses.push((entry.value(), index));
+            }
+        }
+        addresses.sort();
+        SymbolAddressIndex {
+            symbols,
+            addresses,
+        }
+    }
+
+    /// The function symbol with the highest address at or below `addr`,
+    /// provided that `addr` is within its size (or its size is zero), with its
+    /// name.
+    pub fn symbol_for_address(&self,
+                              elf_file: &ElfFile<'a>,
+                              addr: u64)
+                              -> Option<(&'a dyn Entry, &'a str)> {
+        let position = match self.addresses.binary_search(&(addr, usize::MAX)) {
+            Ok(position) => position,
+            Err(0) => return None,
+            Err(position) => position - 1,
+        };
+        let (start, index) = self.addresses[position];
+        let entry = self.symbols.and_then(|symbols| symbols.get(index))?;
+        if entry.size() != 0 && addr - start >= entry.size() {
+            return None;
+        }
+        entry.get_name(elf_file).ok().map(|name| (entry, name))
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct Visibility_(u8);
 
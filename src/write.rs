@@ -0,0 +1,321 @@
+//! Surgical edits to existing ELF files: renaming or removing sections and
+//! changing their flags, in the manner of `strip --remove-section` and
+//! `objcopy --set-section-flags`. Synthesising new files is out of scope.
+//!
+//! Everything covered by the ELF header, the program header table or a segment
+//! is copied to the same offset. It is unchanged except for section indices in
+//! symbol tables and groups (e.g., `st_shndx` in `.dynsym`), which are
+//! renumbered along with the sections so that they still refer to the same
+//! ones. The data of the remaining (non-loaded) sections is laid out after
+//! that, followed by a rebuilt section header string table and the section
+//! header table. Bytes which are not covered by any of these (e.g.,
+//! the data of removed sections) are dropped.
+//!
+//! Like the rest of this crate, fields are read and written in native byte
+//! order.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use ElfFile;
+use header::{self, Class};
+use sections::{self, SectionData, SectionHeader, ShType, SHF_INFO_LINK};
+
+/// Builds a modified copy of an ELF file. Sections are identified by their
+/// index in the original file.
+#[derive(Debug)]
+pub struct Builder<'a> {
+    file: &'a ElfFile<'a>,
+    edits: Vec<SectionEdit>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct SectionEdit {
+    removed: bool,
+    name: Option<String>,
+    flags: Option<u64>,
+}
+
+impl<'a> Builder<'a> {
+    /// Files using extended section numbering (more than `SHN_LORESERVE`
+    /// sections) are not supported.
+    pub fn new(file: &'a ElfFile<'a>) -> Result<Builder<'a>, &'static str> {
+        header::sanity_check(file)?;
+        let pt2 = &file.header.pt2;
+        if pt2.sh_count() == 0 || pt2.sh_str_index() == sections::SHN_XINDEX {
+            return Err("Extended section numbering is not supported");
+        }
+        if pt2.sh_str_index() == sections::SHN_UNDEF {
+            return Err("No section header string table");
+        }
+        let mut edits = Vec::new();
+        edits.resize(pt2.sh_count() as usize, SectionEdit::default());
+        Ok(Builder {
+            file,
+            edits,
+        })
+    }
+
+    pub fn set_name(&mut self, index: u16, name: &str) -> Result<(), &'static str> {
+        self.edit(index)?.name = Some(String::from(name));
+        Ok(())
+    }
+
+    /// Replace the flags (`sh_flags`) of a section. Note that this does not
+    /// change how the file is loaded, that is determined by the segments.
+    pub fn set_flags(&mut self, index: u16, flags: u64) -> Result<(), &'static str> {
+        self.edit(index)?.flags = Some(flags);
+        Ok(())
+    }
+
+    /// Remove a section's header (and its data, unless it is part of a
+    /// segment). Other sections which refer to it (through `link` or `info`),
+    /// symbols defined in it, and groups containing it must be removed too, or
+    /// `build` fails. Remaining sections are renumbered, and references to
+    /// them are fixed up.
+    pub fn remove_section(&mut self, index: u16) -> Result<(), &'static str> {
+        if index as u32 == self.shstr_index() {
+            return Err("Cannot remove the section header string table");
+        }
+        self.edit(index)?.removed = true;
+        Ok(())
+    }
+
+    /// Produce the modified file.
+    pub fn build(&self) -> Result<Vec<u8>, &'static str> {
+        let file = self.file;
+        let pt2 = &file.header.pt2;
+        let word = file.pointer_size();
+        let new_indices = self.new_indices();
+        let limit = file.input.len();
+
+        // The part of the file which is kept in place.
+        let ph_size = pt2.ph_entry_size() as u64 * pt2.ph_count() as u64;
+        let ph_end = pt2.ph_offset()
+                        .checked_add(ph_size)
+                        .ok_or("Program headers out of range")?;
+        let mut fixed_end = (pt2.header_size() as u64).max(ph_end);
+        for ph in file.program_iter() {
+            let end = ph.offset().checked_add(ph.file_size()).ok_or("Segment out of range")?;
+            fixed_end = fixed_end.max(end);
+        }
+        if fixed_end > limit as u64 {
+            return Err("Segment out of range");
+        }
+        let mut out = file.input[..fixed_end as usize].to_vec();
+
+        let mut shstr_table = Vec::new();
+        shstr_table.push(0);
+        // The new section headers, as bytes.
+        let mut headers = Vec::new();
+        let mut shstr_header = 0;
+        for (index, edit) in self.edits.iter().enumerate() {
+            if edit.removed {
+                continue;
+            }
+            let header = file.section_header(index as u16)?;
+            let typ = header.get_type()?;
+            let mut bytes = header.as_bytes().to_vec();
+
+            if index != 0 {
+                let name = match edit.name {
+                    Some(ref name) => name.as_str(),
+                    None if typ == ShType::Null => "",
+                    None => header.get_name(file)?,
+                };
+                put(&mut bytes, SH_NAME, shstr_table.len() as u64, 4)?;
+                shstr_table.extend_from_slice(name.as_bytes());
+                shstr_table.push(0);
+            }
+            if let Some(flags) = edit.flags {
+                put(&mut bytes, SH_FLAGS, flags, word)?;
+            }
+            if header.link() != 0 {
+                let link = remap(&new_indices, header.link())?;
+                put(&mut bytes, sh_link(word), link as u64, 4)?;
+            }
+            let info_is_index = match typ {
+                ShType::Rel | ShType::Rela => true,
+                _ => header.flags() & SHF_INFO_LINK != 0,
+            };
+            if info_is_index && header.info() != 0 {
+                let info = remap(&new_indices, header.info())?;
+                put(&mut bytes, sh_info(word), info as u64, 4)?;
+            }
+
+            let end = header.offset().checked_add(header.size());
+            let offset = if index as u32 == self.shstr_index() {
+                // Written once all the names are known.
+                shstr_header = headers.len();
+                0
+            } else if typ == ShType::Null || typ == ShType::NoBits {
+                header.offset().min(fixed_end)
+            } else if end.is_some_and(|end| end <= fixed_end) {
+                self.remap_data(&mut out, header, header.offset() as usize, &new_indices)?;
+                header.offset()
+            } else if header.offset() < fixed_end {
+                return Err("Section partially overlaps a segment");
+            } else {
+                // Keep data which was aligned in the input (up to 8 bytes)
+                // aligned, even if `sh_addralign` understates it.
+                let aligned_in_input = 1 << header.offset().trailing_zeros().min(3);
+                align(&mut out, header.align(), limit)?;
+                align(&mut out, aligned_in_input, limit)?;
+                let offset = out.len();
+                out.extend_from_slice(header.try_raw_data(file)?);
+                self.remap_data(&mut out, header, offset, &new_indices)?;
+                offset as u64
+            };
+            put(&mut bytes, sh_offset(word), offset, word)?;
+            headers.push(bytes);
+        }
+
+        let offset = out.len() as u64;
+        out.extend_from_slice(&shstr_table);
+        put(&mut headers[shstr_header], sh_offset(word), offset, word)?;
+        put(&mut headers[shstr_header], sh_size(word), shstr_table.len() as u64, word)?;
+
+        align(&mut out, word as u64, limit)?;
+        let table_offset = out.len() as u64;
+        for header in &headers {
+            out.extend_from_slice(header);
+        }
+
+        let shstr_index = remap(&new_indices, self.shstr_index())?;
+        put(&mut out, e_shoff(word), table_offset, word)?;
+        put(&mut out, e_shnum(word), headers.len() as u64, 2)?;
+        put(&mut out, e_shstrndx(word), shstr_index as u64, 2)?;
+        Ok(out)
+    }
+
+    fn edit(&mut self, index: u16) -> Result<&mut SectionEdit, &'static str> {
+        if index == 0 {
+            return Err("Cannot edit the null section");
+        }
+        self.edits.get_mut(index as usize).ok_or("Section index out of range")
+    }
+
+    fn shstr_index(&self) -> u32 {
+        self.file.header.pt2.sh_str_index() as u32
+    }
+
+    // The new index of each section, or `None` if it is removed.
+    fn new_indices(&self) -> Vec<Option<u32>> {
+        let mut next_index = 0;
+        self.edits
+            .iter()
+            .map(|edit| if edit.removed {
+                None
+            } else {
+                next_index += 1;
+                Some(next_index - 1)
+            })
+            .collect()
+    }
+
+    // Fix up section indices in the data of `header` (symbols' sections and
+    // group members), which has been copied to `offset` in `out`.
+    fn remap_data(&self,
+                  out: &mut [u8],
+                  header: SectionHeader<'a>,
+                  offset: usize,
+                  new_indices: &[Option<u32>])
+                  -> Result<(), &'static str> {
+        if !self.edits.iter().any(|edit| edit.removed) {
+            return Ok(());
+        }
+        let data = header.get_data(self.file)?;
+        if let Some(symbols) = data.symbols() {
+            // `st_shndx` is at the end of 32-bit entries, after the name in
+            // 64-bit ones.
+            let (entry_size, st_shndx) = match self.file.class() {
+                Class::ThirtyTwo => (16, 14),
+                _ => (24, 6),
+            };
+            for (i, symbol) in symbols.iter().enumerate() {
+                let index = symbol.shndx();
+                if index == sections::SHN_UNDEF || index >= sections::SHN_LORESERVE {
+                    continue;
+                }
+                let index = remap(new_indices, index as u32)?;
+                put(out, offset + i * entry_size + st_shndx, index as u64, 2)?;
+            }
+        } else if let SectionData::Group { indicies, .. } = data {
+            for (i, &index) in indicies.iter().enumerate() {
+                let index = remap(new_indices, index)?;
+                put(out, offset + 4 + i * 4, index as u64, 4)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn remap(new_indices: &[Option<u32>], index: u32) -> Result<u32, &'static str> {
+    match new_indices.get(index as usize) {
+        Some(&Some(index)) => Ok(index),
+        Some(&None) => Err("Reference to a removed section"),
+        None => Err("Section index out of range"),
+    }
+}
+
+// Offsets of fields in the ELF and section headers, for `word` byte addresses.
+const SH_NAME: usize = 0;
+const SH_FLAGS: usize = 8;
+
+fn sh_offset(word: usize) -> usize {
+    8 + 2 * word
+}
+
+fn sh_size(word: usize) -> usize {
+    8 + 3 * word
+}
+
+fn sh_link(word: usize) -> usize {
+    8 + 4 * word
+}
+
+fn sh_info(word: usize) -> usize {
+    12 + 4 * word
+}
+
+fn e_shoff(word: usize) -> usize {
+    24 + 2 * word
+}
+
+fn e_shnum(word: usize) -> usize {
+    36 + 3 * word
+}
+
+fn e_shstrndx(word: usize) -> usize {
+    38 + 3 * word
+}
+
+// Pad `out` to a multiple of `align`, which must be a power of two no larger
+// than `limit` (the length of the input), so that a corrupt `sh_addralign`
+// cannot make the output huge.
+fn align(out: &mut Vec<u8>, align: u64, limit: usize) -> Result<(), &'static str> {
+    let align = align.max(1);
+    if !align.is_power_of_two() || align > limit as u64 {
+        return Err("Invalid section alignment");
+    }
+    let align = align as usize;
+    let len = out.len().div_ceil(align) * align;
+    out.resize(len, 0);
+    Ok(())
+}
+
+// Write `value` as a native endian integer of `size` bytes at `offset`.
+fn put(out: &mut [u8], offset: usize, value: u64, size: usize) -> Result<(), &'static str> {
+    let out = out.get_mut(offset..offset + size).ok_or("Field out of range")?;
+    match size {
+        2 if value <= u16::MAX as u64 => {
+            out.copy_from_slice(&(value as u16).to_ne_bytes())
+        }
+        4 if value <= u32::MAX as u64 => {
+            out.copy_from_slice(&(value as u32).to_ne_bytes())
+        }
+        8 => out.copy_from_slice(&value.to_ne_bytes()),
+        _ => return Err("Value does not fit in field"),
+    }
+    Ok(())
+}